@@ -0,0 +1,46 @@
+//! Entry decoding and search benchmarks, run against the generated data:
+//! `cargo bench -p japandict-core`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use japandict_core::{build_search_indices, dictionary_info, entry_ref, get_word_entry, search_dictionary};
+
+/// Walk every entry's forms and glosses, as index building does
+fn scan_entries(c: &mut Criterion) {
+    let word_count = dictionary_info().word_count;
+    let mut group = c.benchmark_group("scan_entries");
+    group.bench_function("word_entry", |b| {
+        b.iter(|| {
+            let mut bytes = 0;
+            for idx in 0..word_count {
+                let entry = get_word_entry(idx);
+                bytes += entry.kanji.iter().chain(&entry.kana).map(|s| s.len()).sum::<usize>();
+                bytes += entry.glosses.iter().map(|gloss| gloss.text.len()).sum::<usize>();
+            }
+            black_box(bytes)
+        })
+    });
+    group.bench_function("entry_ref", |b| {
+        b.iter(|| {
+            let mut bytes = 0;
+            for idx in 0..word_count {
+                let entry = entry_ref(idx);
+                bytes += entry.kanji().chain(entry.kana()).map(|s| s.len()).sum::<usize>();
+                bytes += entry.gloss_texts().map(str::len).sum::<usize>();
+            }
+            black_box(bytes)
+        })
+    });
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    build_search_indices();
+    let mut group = c.benchmark_group("search");
+    for query in ["water", "みず", "水", "たべ"] {
+        group.bench_function(query, |b| b.iter(|| search_dictionary(black_box(query))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, scan_entries, search);
+criterion_main!(benches);
@@ -0,0 +1,402 @@
+//! Where the dictionary tables come from
+//!
+//! By default every reader in this crate uses the data embedded through the
+//! dictionary-data crate (the `embedded` feature). A `Dictionary` parsed from
+//! the binary format codegen writes with `--format binary` can be installed
+//! instead, so the web build can fetch its data at runtime rather than carry
+//! it in the wasm binary.
+//!
+//! Binary layout (all integers little-endian, see jmdict-codegen's output.rs):
+//! - magic `JAPADICT` (8 bytes), format version (u32)
+//! - sections up to the end of the file, each: name length (u8), name, kind
+//!   (u8), payload length in bytes (u32), zero padding up to a 4-byte
+//!   boundary, payload
+
+use crate::validate::{validate_tables, ValidationError};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+const BINARY_MAGIC: &[u8; 8] = b"JAPADICT";
+/// Must match codegen's `BINARY_VERSION`
+const BINARY_VERSION: u32 = 3;
+
+const KIND_U8_ARRAY: u8 = 0;
+const KIND_U32_ARRAY: u8 = 1;
+const KIND_STRINGS: u8 = 2;
+const KIND_NUMBER: u8 = 3;
+const KIND_TEXT: u8 = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// Not a dictionary file
+    BadMagic,
+    /// Written by a codegen with a different binary format version
+    UnsupportedVersion(u32),
+    /// The file ends inside a section header or payload
+    Truncated,
+    MissingSection(&'static str),
+    /// A section has the wrong kind or a malformed payload
+    InvalidSection(&'static str),
+    /// The sections parse but fail the integrity checks in `validate`
+    Corrupt(ValidationError),
+    /// A dictionary was already installed
+    AlreadyInstalled,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::BadMagic => write!(f, "not a japandict dictionary file"),
+            LoadError::UnsupportedVersion(version) => {
+                write!(f, "unsupported dictionary format version {} (expected {})", version, BINARY_VERSION)
+            }
+            LoadError::Truncated => write!(f, "dictionary file is truncated"),
+            LoadError::MissingSection(name) => write!(f, "dictionary file has no {} section", name),
+            LoadError::InvalidSection(name) => write!(f, "dictionary file has an invalid {} section", name),
+            LoadError::Corrupt(err) => write!(f, "dictionary file is corrupt: {}", err),
+            LoadError::AlreadyInstalled => write!(f, "a dictionary is already installed"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Corrupt(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Payloads of the sections in a binary file, by name
+struct Sections(HashMap<&'static str, (u8, &'static [u8])>);
+
+/// Decoding a section payload into a table field
+trait FromSection: Sized {
+    const KIND: u8;
+    fn from_payload(payload: &'static [u8]) -> Option<Self>;
+}
+
+impl FromSection for &'static [u8] {
+    const KIND: u8 = KIND_U8_ARRAY;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        Some(payload)
+    }
+}
+
+impl FromSection for &'static [u32] {
+    const KIND: u8 = KIND_U32_ARRAY;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        if !payload.len().is_multiple_of(4) {
+            return None;
+        }
+        // Payloads are 4-byte aligned within the file, so this normally borrows
+        #[cfg(target_endian = "little")]
+        {
+            // SAFETY: every bit pattern is a valid u32
+            let (head, values, tail) = unsafe { payload.align_to::<u32>() };
+            if head.is_empty() && tail.is_empty() {
+                return Some(values);
+            }
+        }
+        let values: Vec<u32> = payload.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+        Some(Box::leak(values.into_boxed_slice()))
+    }
+}
+
+impl FromSection for &'static [&'static str] {
+    const KIND: u8 = KIND_STRINGS;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        let strings = payload
+            .strip_suffix(&[0])
+            .map(|strings| strings.split(|&b| b == 0).map(std::str::from_utf8).collect::<Result<Vec<_>, _>>())
+            .unwrap_or(Ok(Vec::new()))
+            .ok()?;
+        Some(Box::leak(strings.into_boxed_slice()))
+    }
+}
+
+impl FromSection for u64 {
+    const KIND: u8 = KIND_NUMBER;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        Some(u64::from_le_bytes(payload.try_into().ok()?))
+    }
+}
+
+impl FromSection for usize {
+    const KIND: u8 = KIND_NUMBER;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        u64::from_payload(payload).and_then(|value| usize::try_from(value).ok())
+    }
+}
+
+impl FromSection for &'static str {
+    const KIND: u8 = KIND_TEXT;
+    fn from_payload(payload: &'static [u8]) -> Option<Self> {
+        std::str::from_utf8(payload).ok()
+    }
+}
+
+/// Reads a binary file front to back
+struct Cursor {
+    bytes: &'static [u8],
+    pos: usize,
+}
+
+impl Cursor {
+    fn take(&mut self, len: usize) -> Result<&'static [u8], LoadError> {
+        let slice = self.bytes.get(self.pos..self.pos + len).ok_or(LoadError::Truncated)?;
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, LoadError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Skip the padding before a payload
+    fn align(&mut self) -> Result<(), LoadError> {
+        self.take(self.pos.next_multiple_of(4) - self.pos).map(|_| ())
+    }
+}
+
+impl Sections {
+    fn parse(bytes: &'static [u8]) -> Result<Sections, LoadError> {
+        let mut cursor = Cursor { bytes, pos: 0 };
+        if cursor.take(8)? != BINARY_MAGIC {
+            return Err(LoadError::BadMagic);
+        }
+        let version = cursor.u32()?;
+        if version != BINARY_VERSION {
+            return Err(LoadError::UnsupportedVersion(version));
+        }
+
+        let mut sections = HashMap::new();
+        while cursor.pos < bytes.len() {
+            let name_len = cursor.take(1)?[0] as usize;
+            let name = std::str::from_utf8(cursor.take(name_len)?).map_err(|_| LoadError::Truncated)?;
+            let kind = cursor.take(1)?[0];
+            let len = cursor.u32()? as usize;
+            cursor.align()?;
+            sections.insert(name, (kind, cursor.take(len)?));
+        }
+        Ok(Sections(sections))
+    }
+
+    fn get<T: FromSection>(&self, name: &'static str) -> Result<T, LoadError> {
+        let &(kind, payload) = self.0.get(name).ok_or(LoadError::MissingSection(name))?;
+        if kind != T::KIND {
+            return Err(LoadError::InvalidSection(name));
+        }
+        T::from_payload(payload).ok_or(LoadError::InvalidSection(name))
+    }
+}
+
+/// Declares `Tables` once, with how each field is read from the embedded data
+/// and from a binary file: both use the section name.
+macro_rules! tables {
+    ($($field:ident: $ty:ty = $section:ident,)*) => {
+        #[derive(Default)]
+        pub(crate) struct Tables {
+            $(pub $field: $ty,)*
+        }
+
+        impl Tables {
+            #[cfg(feature = "embedded")]
+            fn embedded() -> Tables {
+                Tables { $($field: dictionary_data::$section,)* }
+            }
+
+            fn from_sections(sections: &Sections) -> Result<Tables, LoadError> {
+                Ok(Tables { $($field: sections.get(stringify!($section))?,)* })
+            }
+        }
+    };
+}
+
+tables! {
+    jmdict_strings: &'static [u8] = JMDICT_STRINGS,
+    jmdict_entries: &'static [u8] = JMDICT_ENTRIES,
+    jmdict_entry_offsets: &'static [u32] = JMDICT_ENTRY_OFFSETS,
+    jmdict_string_offsets: &'static [u32] = JMDICT_STRING_OFFSETS,
+    jmdict_id_index: &'static [u32] = JMDICT_ID_INDEX,
+    gloss_languages: &'static [&'static str] = GLOSS_LANGUAGES,
+    word_count: usize = WORD_COUNT,
+    data_checksum: u64 = DATA_CHECKSUM,
+    entry_details: &'static [u32] = ENTRY_DETAILS,
+    entry_detail_offsets: &'static [u32] = ENTRY_DETAIL_OFFSETS,
+
+    kanjidic_strings: &'static [u8] = KANJIDIC_STRINGS,
+    kanjidic_string_offsets: &'static [u32] = KANJIDIC_STRING_OFFSETS,
+    kanjidic_entries: &'static [u8] = KANJIDIC_ENTRIES,
+    kanjidic_entry_offsets: &'static [u32] = KANJIDIC_ENTRY_OFFSETS,
+    kanjidic_literals: &'static [u32] = KANJIDIC_LITERALS,
+    kanjidic_count: usize = KANJIDIC_COUNT,
+
+    radicals: &'static [u32] = RADICALS,
+    radical_strokes: &'static [u8] = RADICAL_STROKES,
+    radical_kanji_offsets: &'static [u32] = RADICAL_KANJI_OFFSETS,
+    radical_kanji: &'static [u32] = RADICAL_KANJI,
+    krad_literals: &'static [u32] = KRAD_LITERALS,
+    krad_component_offsets: &'static [u32] = KRAD_COMPONENT_OFFSETS,
+    krad_components: &'static [u32] = KRAD_COMPONENTS,
+
+    names_strings: &'static [u8] = NAMES_STRINGS,
+    names_string_offsets: &'static [u32] = NAMES_STRING_OFFSETS,
+    names_entries: &'static [u8] = NAMES_ENTRIES,
+    names_entry_offsets: &'static [u32] = NAMES_ENTRY_OFFSETS,
+    name_count: usize = NAME_COUNT,
+
+    example_strings: &'static [u8] = EXAMPLE_STRINGS,
+    example_string_offsets: &'static [u32] = EXAMPLE_STRING_OFFSETS,
+    example_sentences: &'static [u32] = EXAMPLE_SENTENCES,
+    example_entry_ids: &'static [u32] = EXAMPLE_ENTRY_IDS,
+    example_link_offsets: &'static [u32] = EXAMPLE_LINK_OFFSETS,
+    example_links: &'static [u32] = EXAMPLE_LINKS,
+
+    xref_entry_ids: &'static [u32] = XREF_ENTRY_IDS,
+    xref_link_offsets: &'static [u32] = XREF_LINK_OFFSETS,
+    xref_links: &'static [u32] = XREF_LINKS,
+
+    jmdict_version: &'static str = JMDICT_VERSION,
+    jmdict_date: &'static str = JMDICT_DATE,
+    entry_limit: usize = ENTRY_LIMIT,
+    codegen_version: &'static str = CODEGEN_VERSION,
+    codegen_filters: &'static str = CODEGEN_FILTERS,
+}
+
+/// The tables of a binary file, before any integrity checks
+pub(crate) fn parse_tables(bytes: &'static [u8]) -> Result<Tables, LoadError> {
+    Tables::from_sections(&Sections::parse(bytes)?)
+}
+
+static INSTALLED: OnceLock<Tables> = OnceLock::new();
+static DEFAULT: OnceLock<Tables> = OnceLock::new();
+
+/// The installed dictionary, or the embedded data (empty without the
+/// `embedded` feature)
+pub(crate) fn tables() -> &'static Tables {
+    INSTALLED.get().unwrap_or_else(|| {
+        DEFAULT.get_or_init(|| {
+            #[cfg(feature = "embedded")]
+            return Tables::embedded();
+            #[cfg(not(feature = "embedded"))]
+            Tables::default()
+        })
+    })
+}
+
+/// Dictionary data loaded at runtime from codegen's binary format
+pub struct Dictionary {
+    tables: Tables,
+}
+
+impl Dictionary {
+    /// Parse a binary dictionary and run the `validate` checks on it, so a
+    /// corrupt or truncated file is rejected here rather than panicking in a
+    /// search. The bytes are leaked, since entries hand out `&'static str`s: a
+    /// dictionary lives as long as the program, like the embedded data.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Dictionary, LoadError> {
+        let tables = parse_tables(Box::leak(bytes.into_boxed_slice()))?;
+        validate_tables(&tables).map_err(LoadError::Corrupt)?;
+        Ok(Dictionary { tables })
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.tables.word_count
+    }
+
+    /// Make this the data every function in the crate reads. Install before
+    /// `build_search_indices`, which indexes whatever data is current.
+    pub fn install(self) -> Result<(), LoadError> {
+        INSTALLED.set(self.tables).map_err(|_| LoadError::AlreadyInstalled)
+    }
+}
+
+/// Whether a `Dictionary` has been installed over the embedded data
+pub fn is_installed() -> bool {
+    INSTALLED.get().is_some()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::dictionary::EntryRef;
+    use jmdict_codegen::sample;
+
+    /// A one-word dictionary (犬, いぬ, "dog") in codegen's binary format, with
+    /// the tables in the order codegen writes them
+    pub(crate) fn sample() -> Vec<u8> {
+        sample::dictionary(&[sample::word("1000000", Some("犬"), "いぬ", &[("eng", "dog")])])
+    }
+
+    fn leak(bytes: Vec<u8>) -> &'static [u8] {
+        Box::leak(bytes.into_boxed_slice())
+    }
+
+    #[test]
+    fn reads_back_what_codegen_writes() {
+        let tables = parse_tables(leak(sample())).unwrap();
+        assert_eq!(tables.word_count, 1);
+        assert_eq!(tables.gloss_languages, ["eng"]);
+        assert_eq!(tables.jmdict_id_index, [0]);
+        assert_eq!(tables.entry_detail_offsets, [0, 12]);
+        assert_eq!(tables.jmdict_version, "3.6.1");
+        assert_eq!(tables.codegen_filters, "");
+
+        let entry = EntryRef::new(
+            tables.jmdict_entries,
+            tables.jmdict_strings,
+            tables.jmdict_string_offsets,
+            tables.gloss_languages,
+        );
+        assert_eq!(entry.id(), "1000000");
+        assert_eq!(entry.kanji().collect::<Vec<_>>(), ["犬"]);
+        assert_eq!(entry.kana().collect::<Vec<_>>(), ["いぬ"]);
+        assert_eq!(entry.glosses().collect::<Vec<_>>(), [("eng", "dog")]);
+        assert_eq!(entry.gloss_senses().collect::<Vec<_>>(), [0]);
+        assert_eq!(entry.pos().collect::<Vec<_>>(), ["n"]);
+        assert_eq!(entry.accents().collect::<Vec<_>>(), [&[0u8][..]]);
+        assert!(entry.is_common());
+    }
+
+    #[test]
+    fn loads_a_valid_dictionary() {
+        let dictionary = Dictionary::from_bytes(sample()).unwrap();
+        assert_eq!(dictionary.word_count(), 1);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let mut bytes = sample();
+        bytes[..8].copy_from_slice(b"NOTADICT");
+        assert_eq!(Dictionary::from_bytes(bytes).err(), Some(LoadError::BadMagic));
+
+        let mut bytes = sample();
+        bytes[8..12].copy_from_slice(&(BINARY_VERSION + 1).to_le_bytes());
+        assert_eq!(Dictionary::from_bytes(bytes).err(), Some(LoadError::UnsupportedVersion(BINARY_VERSION + 1)));
+    }
+
+    #[test]
+    fn rejects_every_truncation() {
+        let bytes = sample();
+        for len in 0..bytes.len() {
+            assert!(Dictionary::from_bytes(bytes[..len].to_vec()).is_err(), "accepted the first {} bytes", len);
+        }
+    }
+
+    #[test]
+    fn rejects_corrupt_payloads() {
+        let bytes = sample();
+        // Flip a byte of the first payload, the JMdict strings
+        let payload = (8 + 4 + 1 + "JMDICT_STRINGS".len() + 1 + 4usize).next_multiple_of(4);
+        let mut corrupt = bytes.clone();
+        corrupt[payload] ^= 0x01;
+        assert!(matches!(
+            Dictionary::from_bytes(corrupt).err(),
+            Some(LoadError::Corrupt(ValidationError::ChecksumMismatch { .. }))
+        ));
+    }
+}
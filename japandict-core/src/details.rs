@@ -0,0 +1,111 @@
+//! Per-form markers and per-sense notes of word entries, for detail views
+//!
+//! Kept apart from the packed entries (layout in jmdict-codegen's details.rs),
+//! so search and result lists don't decode them.
+
+use crate::data::tables;
+use crate::dictionary::{entry_ref, read_null_terminated, EntryError};
+use crate::validate::{check_entry, check_string, ValidationError};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FormDetails {
+    pub text: &'static str,
+    pub common: bool,
+    /// JMdict tags such as `ateji`, `rK` (rarely used kanji form) or `ik`
+    pub tags: Vec<&'static str>,
+    /// Priority markers such as `news1` or `ichi1`, only in sources that keep them
+    pub priority: Vec<&'static str>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SenseDetails {
+    pub pos: Vec<&'static str>,
+    /// Usage notes such as `uk` (usually kana) or `col` (colloquial)
+    pub misc: Vec<&'static str>,
+    /// Field of application, e.g. `comp` or `med`
+    pub field: Vec<&'static str>,
+    pub dialect: Vec<&'static str>,
+    /// Free-text information
+    pub info: Vec<&'static str>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EntryDetails {
+    pub kanji: Vec<FormDetails>,
+    pub kana: Vec<FormDetails>,
+    /// In the same order as the entry's gloss senses
+    pub senses: Vec<SenseDetails>,
+}
+
+const TABLE: &str = "ENTRY_DETAILS";
+
+/// Reads an entry's run of the details table front to back, failing instead
+/// of panicking when the run is shorter than its counts say
+struct Values {
+    run: std::slice::Iter<'static, u32>,
+    entry: usize,
+}
+
+impl Values {
+    fn next(&mut self) -> Result<u32, EntryError> {
+        let overrun = ValidationError::RecordOverrun { table: TABLE, record: self.entry };
+        self.run.next().copied().ok_or(EntryError::Corrupt(overrun))
+    }
+
+    fn strings(&mut self, count: u32) -> Result<Vec<&'static str>, EntryError> {
+        (0..count).map(|_| self.string()).collect()
+    }
+
+    fn string(&mut self) -> Result<&'static str, EntryError> {
+        let index = self.next()?;
+        let missing = ValidationError::ReferenceOutOfRange { table: TABLE, record: self.entry, index };
+        let &offset = tables().jmdict_string_offsets.get(index as usize).ok_or(EntryError::Corrupt(missing))?;
+        check_string("JMDICT_STRINGS", tables().jmdict_strings, index as usize, offset).map_err(EntryError::Corrupt)?;
+        Ok(read_null_terminated(tables().jmdict_strings, offset))
+    }
+}
+
+/// Details of the entry at `index`, checked like `try_get_word_entry`
+pub fn entry_details(index: usize) -> Result<EntryDetails, EntryError> {
+    let count = tables().jmdict_entry_offsets.len();
+    if index >= count {
+        return Err(EntryError::IndexOutOfRange { index, count });
+    }
+    check_entry(tables(), index).map_err(EntryError::Corrupt)?;
+
+    let offsets = tables().entry_detail_offsets;
+    let (start, end) = match (offsets.get(index), offsets.get(index + 1)) {
+        (Some(&start), Some(&end)) => (start, end),
+        _ => {
+            let mismatch = ValidationError::LengthMismatch { table: "ENTRY_DETAIL_OFFSETS", expected: count + 1, actual: offsets.len() };
+            return Err(EntryError::Corrupt(mismatch));
+        }
+    };
+    let run = tables().entry_details.get(start as usize..end as usize).ok_or(EntryError::Corrupt(
+        ValidationError::OffsetOutOfRange { table: "ENTRY_DETAIL_OFFSETS", position: index, offset: start },
+    ))?;
+    let mut values = Values { run: run.iter(), entry: index };
+
+    let entry = entry_ref(index);
+    let mut form = |text: &'static str| {
+        let common = values.next()? == 1;
+        let (tag_count, priority_count) = (values.next()?, values.next()?);
+        Ok(FormDetails { text, common, tags: values.strings(tag_count)?, priority: values.strings(priority_count)? })
+    };
+    let kanji = entry.kanji().map(&mut form).collect::<Result<_, EntryError>>()?;
+    let kana = entry.kana().map(&mut form).collect::<Result<_, EntryError>>()?;
+
+    let mut senses = Vec::new();
+    while !values.run.as_slice().is_empty() {
+        let counts: [u32; 5] = [values.next()?, values.next()?, values.next()?, values.next()?, values.next()?];
+        senses.push(SenseDetails {
+            pos: values.strings(counts[0])?,
+            misc: values.strings(counts[1])?,
+            field: values.strings(counts[2])?,
+            dialect: values.strings(counts[3])?,
+            info: values.strings(counts[4])?,
+        });
+    }
+
+    Ok(EntryDetails { kanji, kana, senses })
+}
@@ -1,71 +1,295 @@
-use dictionary_data::*;
+use crate::validate::{check_entry, ValidationError};
+use crate::data::tables;
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gloss {
+    /// JMdict language code, e.g. "eng", "ger", "fre"
+    pub lang: &'static str,
+    pub text: &'static str,
+    /// Index of the JMdict sense the gloss belongs to, counting from 0
+    pub sense: u8,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct WordEntry {
     pub id: &'static str,
     pub kanji: Vec<&'static str>,
     pub kana: Vec<&'static str>,
+    /// The English glosses, same as `glosses_in("eng")`
     pub english: Vec<&'static str>,
+    /// Glosses in every language the data was generated with
+    pub glosses: Vec<Gloss>,
     pub pos: Vec<&'static str>,
     pub is_common: bool,
+    /// Pitch accent downstep positions for each reading in `kana` (0 = heiban)
+    pub accents: Vec<Vec<u8>>,
+}
+
+impl WordEntry {
+    pub fn glosses_in(&self, lang: &str) -> Vec<&'static str> {
+        self.glosses.iter().filter(|gloss| gloss.lang == lang).map(|gloss| gloss.text).collect()
+    }
+
+    /// Glosses in `lang` grouped by sense, in JMdict sense order. Senses with no
+    /// gloss in `lang` are left out.
+    pub fn senses_in(&self, lang: &str) -> Vec<Vec<&'static str>> {
+        self.senses_with_index_in(lang).into_iter().map(|(_, texts)| texts).collect()
+    }
+
+    /// Like `senses_in`, with each group's sense index (`Gloss::sense`), which
+    /// also indexes `EntryDetails::senses`
+    pub fn senses_with_index_in(&self, lang: &str) -> Vec<(u8, Vec<&'static str>)> {
+        let mut senses: Vec<(u8, Vec<&'static str>)> = Vec::new();
+        for gloss in self.glosses.iter().filter(|gloss| gloss.lang == lang) {
+            match senses.last_mut() {
+                Some((sense, texts)) if *sense == gloss.sense => texts.push(gloss.text),
+                _ => senses.push((gloss.sense, vec![gloss.text])),
+            }
+        }
+        senses
+    }
 }
 
-fn read_string(offset: u32) -> &'static str {
+/// Gloss languages available in the generated data, in codegen `--languages` order
+pub fn languages() -> &'static [&'static str] {
+    tables().gloss_languages
+}
+
+/// English when the data has it, otherwise the first generated language
+pub fn default_language() -> &'static str {
+    tables().gloss_languages.iter().copied().find(|&lang| lang == "eng").or(tables().gloss_languages.first().copied()).unwrap_or("eng")
+}
+
+pub(crate) fn read_null_terminated(strings: &[u8], offset: u32) -> &str {
     let start = offset as usize;
     let mut end = start;
-    while end < JMDICT_STRINGS.len() && JMDICT_STRINGS[end] != 0 {
+    while end < strings.len() && strings[end] != 0 {
         end += 1;
     }
-    unsafe { std::str::from_utf8_unchecked(&JMDICT_STRINGS[start..end]) }
+    // Loaded dictionaries are validated, so this only fails on corrupt
+    // embedded data; an empty string beats undefined behaviour there
+    std::str::from_utf8(&strings[start..end]).unwrap_or_default()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryError {
+    /// No entry at this index
+    IndexOutOfRange { index: usize, count: usize },
+    /// No entry with this JMdict id
+    UnknownId(String),
+    /// The entry's packed data is inconsistent
+    Corrupt(ValidationError),
+}
+
+impl fmt::Display for EntryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EntryError::IndexOutOfRange { index, count } => {
+                write!(f, "entry index {} out of range ({} entries)", index, count)
+            }
+            EntryError::UnknownId(id) => write!(f, "no entry with JMdict id {}", id),
+            EntryError::Corrupt(err) => write!(f, "corrupt entry: {}", err),
+        }
+    }
 }
 
+impl std::error::Error for EntryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EntryError::Corrupt(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Like `get_word_entry`, but checks the index, the entry's offsets and its strings first
+pub fn try_get_word_entry(index: usize) -> Result<WordEntry, EntryError> {
+    let count = tables().jmdict_entry_offsets.len();
+    if index >= count {
+        return Err(EntryError::IndexOutOfRange { index, count });
+    }
+    check_entry(tables(), index).map_err(EntryError::Corrupt)?;
+    Ok(get_word_entry(index))
+}
+
+/// Index of the entry with a JMdict id, found by binary search over the id table.
+/// Ids are stable across data builds, unlike indices. Every entry the search
+/// visits is checked first, so corrupt data fails with `EntryError::Corrupt`.
+pub fn index_of_jmdict_id(id: &str) -> Result<usize, EntryError> {
+    let id_index = tables().jmdict_id_index;
+    let count = tables().jmdict_entry_offsets.len();
+    let (mut low, mut high) = (0, id_index.len());
+    while low < high {
+        let position = low + (high - low) / 2;
+        let index = id_index[position] as usize;
+        if index >= count {
+            return Err(EntryError::Corrupt(ValidationError::IdIndexOutOfRange { position, entry: id_index[position] }));
+        }
+        check_entry(tables(), index).map_err(EntryError::Corrupt)?;
+        match entry_ref(index).id().cmp(id) {
+            Ordering::Less => low = position + 1,
+            Ordering::Greater => high = position,
+            Ordering::Equal => return Ok(index),
+        }
+    }
+    Err(EntryError::UnknownId(id.to_string()))
+}
+
+pub fn get_by_jmdict_id(id: &str) -> Result<WordEntry, EntryError> {
+    try_get_word_entry(index_of_jmdict_id(id)?)
+}
+
+/// Panics on an out-of-range index; see `try_get_word_entry`
 pub fn get_word_entry(index: usize) -> WordEntry {
-    let offset = JMDICT_ENTRY_OFFSETS[index] as usize;
-    let data = &JMDICT_ENTRIES[offset..];
-    
-    let id_idx = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-    let kanji_count = data[4] as usize;
-    let kana_count = data[5] as usize;
-    let english_count = data[6] as usize;
-    let pos_count = data[7] as usize;
-    let is_common = data[8] != 0;
-    
-    let mut pos = 9;
-    let mut kanji = Vec::new();
-    let mut kana = Vec::new();
-    let mut english = Vec::new();
-    let mut pos_vec = Vec::new();
-    
-    // Read kanji indices
-    for _ in 0..kanji_count {
-        let idx = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-        kanji.push(read_string(JMDICT_STRING_OFFSETS[idx as usize]));
-        pos += 4;
-    }
-    
-    // Read kana indices
-    for _ in 0..kana_count {
-        let idx = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-        kana.push(read_string(JMDICT_STRING_OFFSETS[idx as usize]));
-        pos += 4;
-    }
-    
-    // Read english indices
-    for _ in 0..english_count {
-        let idx = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-        english.push(read_string(JMDICT_STRING_OFFSETS[idx as usize]));
-        pos += 4;
-    }
-    
-    // Read pos indices
-    for _ in 0..pos_count {
-        let idx = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-        pos_vec.push(read_string(JMDICT_STRING_OFFSETS[idx as usize]));
-        pos += 4;
-    }
-    
-    let id_offset_base = KANJI_STRINGS_COUNT + KANA_STRINGS_COUNT + ENGLISH_STRINGS_COUNT + POS_STRINGS_COUNT;
-    let id = read_string(JMDICT_STRING_OFFSETS[(id_offset_base + id_idx) as usize]);
-    
-    WordEntry { id, kanji, kana, english, pos: pos_vec, is_common }
-}
\ No newline at end of file
+    entry_ref(index).to_word_entry()
+}
+
+/// Borrowed view of a packed word entry. Fields are decoded lazily off the
+/// packed bytes, so scanning entries this way allocates nothing; use
+/// `WordEntry` when an owned copy is needed.
+#[derive(Clone, Copy)]
+pub struct EntryRef<'a> {
+    data: &'a [u8],
+    strings: &'a [u8],
+    string_offsets: &'a [u32],
+    languages: &'a [&'a str],
+    kanji_count: usize,
+    kana_count: usize,
+    gloss_count: usize,
+    pos_count: usize,
+}
+
+/// View of the entry at `index`. Panics on an out-of-range index.
+pub fn entry_ref(index: usize) -> EntryRef<'static> {
+    EntryRef::new(
+        &tables().jmdict_entries[tables().jmdict_entry_offsets[index] as usize..],
+        tables().jmdict_strings,
+        tables().jmdict_string_offsets,
+        tables().gloss_languages,
+    )
+}
+
+impl<'a> EntryRef<'a> {
+    /// `data` starts at the entry's first byte; the layout is documented in
+    /// jmdict-codegen's entry packing.
+    pub(crate) fn new(data: &'a [u8], strings: &'a [u8], string_offsets: &'a [u32], languages: &'a [&'a str]) -> Self {
+        let count = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+        EntryRef {
+            data,
+            strings,
+            string_offsets,
+            languages,
+            kanji_count: count(4),
+            kana_count: count(6),
+            gloss_count: count(8),
+            pos_count: count(10),
+        }
+    }
+
+    fn u32_at(&self, pos: usize) -> u32 {
+        u32::from_le_bytes([self.data[pos], self.data[pos + 1], self.data[pos + 2], self.data[pos + 3]])
+    }
+
+    fn string(&self, idx: u32) -> &'a str {
+        read_null_terminated(self.strings, self.string_offsets[idx as usize])
+    }
+
+    /// Strings for `count` indices starting at index slot `first`
+    fn strings_at(&self, first: usize, count: usize) -> impl ExactSizeIterator<Item = &'a str> + 'a {
+        let entry = *self;
+        (first..first + count).map(move |slot| entry.string(entry.u32_at(13 + slot * 4)))
+    }
+
+    /// Byte position just past the string indices
+    fn indices_end(&self) -> usize {
+        13 + (self.kanji_count + self.kana_count + self.gloss_count + self.pos_count) * 4
+    }
+
+    pub fn id(&self) -> &'a str {
+        self.string(self.u32_at(0))
+    }
+
+    pub fn is_common(&self) -> bool {
+        self.data[12] != 0
+    }
+
+    pub fn kanji(&self) -> impl ExactSizeIterator<Item = &'a str> + 'a {
+        self.strings_at(0, self.kanji_count)
+    }
+
+    pub fn kana(&self) -> impl ExactSizeIterator<Item = &'a str> + 'a {
+        self.strings_at(self.kanji_count, self.kana_count)
+    }
+
+    /// Gloss texts in every generated language, in entry order
+    pub fn gloss_texts(&self) -> impl ExactSizeIterator<Item = &'a str> + 'a {
+        self.strings_at(self.kanji_count + self.kana_count, self.gloss_count)
+    }
+
+    pub fn pos(&self) -> impl ExactSizeIterator<Item = &'a str> + 'a {
+        self.strings_at(self.kanji_count + self.kana_count + self.gloss_count, self.pos_count)
+    }
+
+    /// Pitch accent downstep positions, one slice per reading in `kana()`
+    pub fn accents(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let data = self.data;
+        let mut pos = self.indices_end();
+        (0..self.kana_count).map(move |_| {
+            let count = data[pos] as usize;
+            let accents = &data[pos + 1..pos + 1 + count];
+            pos += 1 + count;
+            accents
+        })
+    }
+
+    /// Byte position of the gloss languages, just past the accent groups
+    fn languages_start(&self) -> usize {
+        let mut pos = self.indices_end();
+        for _ in 0..self.kana_count {
+            pos += 1 + self.data[pos] as usize;
+        }
+        pos
+    }
+
+    /// Language code and text of each gloss
+    pub fn glosses(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        let pos = self.languages_start();
+        let languages = self.languages;
+        self.data[pos..pos + self.gloss_count]
+            .iter()
+            .map(move |&lang| languages[lang as usize])
+            .zip(self.gloss_texts())
+    }
+
+    pub fn glosses_in(&self, lang: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.glosses().filter(move |&(gloss_lang, _)| gloss_lang == lang).map(|(_, text)| text)
+    }
+
+    /// Sense index of each gloss, in `gloss_texts()` order
+    pub fn gloss_senses(&self) -> impl ExactSizeIterator<Item = u8> + 'a {
+        let pos = self.languages_start() + self.gloss_count;
+        self.data[pos..pos + self.gloss_count].iter().copied()
+    }
+}
+
+impl EntryRef<'static> {
+    pub fn to_word_entry(&self) -> WordEntry {
+        let glosses: Vec<Gloss> = self
+            .glosses()
+            .zip(self.gloss_senses())
+            .map(|((lang, text), sense)| Gloss { lang, text, sense })
+            .collect();
+        WordEntry {
+            id: self.id(),
+            kanji: self.kanji().collect(),
+            kana: self.kana().collect(),
+            english: glosses.iter().filter(|gloss| gloss.lang == "eng").map(|gloss| gloss.text).collect(),
+            glosses,
+            pos: self.pos().collect(),
+            is_common: self.is_common(),
+            accents: self.accents().map(<[u8]>::to_vec).collect(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Tatoeba example sentences linked to word entries
+
+use crate::dictionary::read_null_terminated;
+use crate::data::tables;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExampleSentence {
+    /// Tatoeba sentence id of the Japanese sentence
+    pub id: u32,
+    pub japanese: &'static str,
+    pub english: &'static str,
+    /// 1-based JMdict sense the sentence illustrates, if the corpus says
+    pub sense: Option<u8>,
+    /// The form of the headword as it appears in the sentence
+    pub highlight: &'static str,
+}
+
+impl ExampleSentence {
+    /// Split the Japanese sentence around the first occurrence of the
+    /// headword: (before, headword, after). The headword part is empty if it
+    /// can't be found.
+    pub fn split_highlight(&self) -> (&'static str, &'static str, &'static str) {
+        match self.japanese.find(self.highlight).filter(|_| !self.highlight.is_empty()) {
+            Some(start) => {
+                let end = start + self.highlight.len();
+                (&self.japanese[..start], &self.japanese[start..end], &self.japanese[end..])
+            }
+            None => (self.japanese, "", ""),
+        }
+    }
+}
+
+fn read_string(index: u32) -> &'static str {
+    read_null_terminated(tables().example_strings, tables().example_string_offsets[index as usize])
+}
+
+/// Example sentences for a JMdict entry id, checked examples first
+pub fn examples_for(entry_id: &str) -> Vec<ExampleSentence> {
+    let Some(position) = entry_id.parse::<u32>().ok().and_then(|id| tables().example_entry_ids.binary_search(&id).ok()) else {
+        return Vec::new();
+    };
+    
+    let start = tables().example_link_offsets[position] as usize * 3;
+    let end = tables().example_link_offsets[position + 1] as usize * 3;
+    
+    tables().example_links[start..end]
+        .chunks_exact(3)
+        .map(|link| {
+            let sentence = &tables().example_sentences[link[0] as usize * 3..][..3];
+            ExampleSentence {
+                id: sentence[0],
+                japanese: read_string(sentence[1]),
+                english: read_string(sentence[2]),
+                sense: u8::try_from(link[1]).ok().filter(|&sense| sense != 0),
+                highlight: read_string(link[2]),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,56 @@
+//! Metadata about the generated dictionary data, for bug reports and footers
+
+use crate::data::tables;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DictionaryInfo {
+    /// jmdict-simplified release the data was generated from
+    pub jmdict_version: &'static str,
+    /// Date of the JMdict build inside that release
+    pub dict_date: &'static str,
+    /// Gloss languages included
+    pub languages: &'static [&'static str],
+    /// Word limit passed to codegen, if any
+    pub entry_limit: Option<usize>,
+    /// Codegen subset filters, e.g. "common-only pos=v1,v5*" (empty for the full dictionary)
+    pub filters: &'static str,
+    pub codegen_version: &'static str,
+    pub word_count: usize,
+    pub kanji_count: usize,
+    pub name_count: usize,
+}
+
+pub fn dictionary_info() -> DictionaryInfo {
+    DictionaryInfo {
+        jmdict_version: tables().jmdict_version,
+        dict_date: tables().jmdict_date,
+        languages: tables().gloss_languages,
+        entry_limit: match tables().entry_limit {
+            0 => None,
+            limit => Some(limit),
+        },
+        filters: tables().codegen_filters,
+        codegen_version: tables().codegen_version,
+        word_count: tables().word_count,
+        kanji_count: tables().kanjidic_count,
+        name_count: tables().name_count,
+    }
+}
+
+impl fmt::Display for DictionaryInfo {
+    /// One-line summary, e.g. "JMdict 3.6.1 (2025-08-18), eng, 213000 words, codegen 0.1.0"
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.jmdict_version.is_empty() {
+            return write!(f, "no dictionary data generated");
+        }
+        write!(f, "JMdict {} ({}), {}, {} words", self.jmdict_version, self.dict_date, self.languages.join("/"), self.word_count)?;
+        if !self.filters.is_empty() {
+            write!(f, " ({})", self.filters)?;
+        }
+        if let Some(limit) = self.entry_limit {
+            write!(f, " (limit {})", limit)?;
+        }
+        write!(f, ", codegen {}", self.codegen_version)
+    }
+}
@@ -0,0 +1,79 @@
+//! Single-kanji information from KANJIDIC2
+
+use crate::dictionary::read_null_terminated;
+use crate::data::tables;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KanjiInfo {
+    pub literal: char,
+    pub on_readings: Vec<&'static str>,
+    pub kun_readings: Vec<&'static str>,
+    pub meanings: Vec<&'static str>,
+    pub stroke_count: u8,
+    pub grade: Option<u8>,
+    pub jlpt: Option<u8>,
+    /// Rank among the 2500 most used kanji in newspapers
+    pub frequency: Option<u16>,
+    /// Classical (Kangxi) radical number, 1-214
+    pub radical: u8,
+}
+
+impl KanjiInfo {
+    /// The radical as a character from the Kangxi Radicals block
+    pub fn radical_char(&self) -> Option<char> {
+        if (1..=214).contains(&self.radical) {
+            char::from_u32(0x2F00 + self.radical as u32 - 1)
+        } else {
+            None
+        }
+    }
+}
+
+fn read_string(index: u32) -> &'static str {
+    read_null_terminated(tables().kanjidic_strings, tables().kanjidic_string_offsets[index as usize])
+}
+
+/// Stroke count without decoding the rest of the entry
+pub fn stroke_count(literal: char) -> Option<u8> {
+    let index = tables().kanjidic_literals.binary_search(&(literal as u32)).ok()?;
+    Some(tables().kanjidic_entries[tables().kanjidic_entry_offsets[index] as usize])
+}
+
+pub fn get_kanji(literal: char) -> Option<KanjiInfo> {
+    let index = tables().kanjidic_literals.binary_search(&(literal as u32)).ok()?;
+    let offset = tables().kanjidic_entry_offsets[index] as usize;
+    let data = &tables().kanjidic_entries[offset..];
+    
+    let stroke_count = data[0];
+    let grade = data[1];
+    let jlpt = data[2];
+    let frequency = u16::from_le_bytes([data[3], data[4]]);
+    let radical = data[5];
+    let on_count = data[6] as usize;
+    let kun_count = data[7] as usize;
+    let meaning_count = data[8] as usize;
+    
+    let mut strings = data[9..]
+        .chunks_exact(4)
+        .map(|idx| read_string(u32::from_le_bytes([idx[0], idx[1], idx[2], idx[3]])));
+    let on_readings = strings.by_ref().take(on_count).collect();
+    let kun_readings = strings.by_ref().take(kun_count).collect();
+    let meanings = strings.take(meaning_count).collect();
+    
+    Some(KanjiInfo {
+        literal,
+        on_readings,
+        kun_readings,
+        meanings,
+        stroke_count,
+        grade: (grade != 0).then_some(grade),
+        jlpt: (jlpt != 0).then_some(jlpt),
+        frequency: (frequency != 0).then_some(frequency),
+        radical,
+    })
+}
+
+/// Information for every kanji in `text` that KANJIDIC2 knows about
+pub fn kanji_in(text: &str) -> Vec<KanjiInfo> {
+    text.chars().filter_map(get_kanji).collect()
+}
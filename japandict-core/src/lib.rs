@@ -1,5 +1,26 @@
+pub mod data;
+pub mod details;
 pub mod dictionary;
+pub mod examples;
+pub mod info;
+pub mod kanji;
+pub mod names;
+pub mod parse;
+pub mod pitch;
+pub mod radicals;
 pub mod search;
+pub mod validate;
+pub mod xrefs;
 
+pub use data::*;
+pub use details::*;
 pub use dictionary::*;
-pub use search::*;
\ No newline at end of file
+pub use examples::*;
+pub use info::*;
+pub use kanji::*;
+pub use names::*;
+pub use parse::*;
+pub use radicals::*;
+pub use search::*;
+pub use validate::*;
+pub use xrefs::*;
\ No newline at end of file
@@ -0,0 +1,39 @@
+//! Proper names from JMnedict, stored apart from the word entries
+
+use crate::dictionary::read_null_terminated;
+use crate::data::tables;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameEntry {
+    pub id: &'static str,
+    pub kanji: Vec<&'static str>,
+    pub kana: Vec<&'static str>,
+    pub translations: Vec<&'static str>,
+    /// JMnedict name types, e.g. "surname", "place", "given", "company"
+    pub name_types: Vec<&'static str>,
+}
+
+fn read_string(index: u32) -> &'static str {
+    read_null_terminated(tables().names_strings, tables().names_string_offsets[index as usize])
+}
+
+pub fn get_name_entry(index: usize) -> NameEntry {
+    let offset = tables().names_entry_offsets[index] as usize;
+    let data = &tables().names_entries[offset..];
+    
+    let id_idx = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let kanji_count = data[4] as usize;
+    let kana_count = data[5] as usize;
+    let translation_count = data[6] as usize;
+    let type_count = data[7] as usize;
+    
+    let mut strings = data[8..]
+        .chunks_exact(4)
+        .map(|idx| read_string(u32::from_le_bytes([idx[0], idx[1], idx[2], idx[3]])));
+    let kanji = strings.by_ref().take(kanji_count).collect();
+    let kana = strings.by_ref().take(kana_count).collect();
+    let translations = strings.by_ref().take(translation_count).collect();
+    let name_types = strings.take(type_count).collect();
+    
+    NameEntry { id: read_string(id_idx), kanji, kana, translations, name_types }
+}
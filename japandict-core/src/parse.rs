@@ -0,0 +1,60 @@
+//! Splitting Japanese text into dictionary words
+//!
+//! Greedy longest match against the kanji and kana forms in the search
+//! indices, so `build_search_indices` must have run. Inflected forms are not
+//! recognised: 食べた splits into 食べ (if that is a form) or unknown text.
+
+use crate::dictionary::entry_ref;
+use crate::search::entries_with_form;
+
+/// Longest form tried at each position, in characters
+const MAX_WORD_CHARS: usize = 16;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    /// Byte offset of `text` in the parsed string
+    pub start: usize,
+    /// Indices of the entries with `text` as a form, common words first; empty
+    /// for a run of text no entry matches
+    pub entries: Vec<usize>,
+}
+
+/// Split `text` into the longest dictionary forms found from left to right.
+/// Characters no form starts with are grouped into tokens without entries.
+pub fn parse_text(text: &str) -> Vec<Token<'_>> {
+    let boundaries: Vec<usize> = text.char_indices().map(|(i, _)| i).chain([text.len()]).collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut unknown_start = None;
+
+    let mut pos = 0;
+    while pos + 1 < boundaries.len() {
+        let start = boundaries[pos];
+        let longest = (pos + 1..boundaries.len().min(pos + 1 + MAX_WORD_CHARS))
+            .rev()
+            .find_map(|end| {
+                let entries = entries_with_form(&text[start..boundaries[end]]);
+                (!entries.is_empty()).then_some((end, entries))
+            });
+
+        match longest {
+            Some((end, mut entries)) => {
+                if let Some(unknown) = unknown_start.take() {
+                    tokens.push(Token { text: &text[unknown..start], start: unknown, entries: Vec::new() });
+                }
+                entries.sort_by_key(|&index| !entry_ref(index).is_common());
+                tokens.push(Token { text: &text[start..boundaries[end]], start, entries });
+                pos = end;
+            }
+            None => {
+                unknown_start.get_or_insert(start);
+                pos += 1;
+            }
+        }
+    }
+    if let Some(unknown) = unknown_start {
+        tokens.push(Token { text: &text[unknown..], start: unknown, entries: Vec::new() });
+    }
+
+    tokens
+}
@@ -0,0 +1,77 @@
+//! Pitch accent helpers
+//!
+//! Accents are stored as downstep positions in Tokyo notation: 0 is heiban
+//! (no drop), 1 is atamadaka, and n drops after the nth mora.
+
+/// Split a kana reading into morae. Small kana (ゃ, ュ, ぁ...) belong to the
+/// preceding mora; っ and ー count as morae of their own.
+pub fn morae(kana: &str) -> Vec<&str> {
+    let mut morae: Vec<&str> = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in kana.char_indices() {
+        if i > start && !is_small_kana(c) {
+            morae.push(&kana[start..i]);
+            start = i;
+        }
+    }
+    if start < kana.len() {
+        morae.push(&kana[start..]);
+    }
+
+    morae
+}
+
+fn is_small_kana(c: char) -> bool {
+    matches!(
+        c,
+        'ゃ' | 'ゅ' | 'ょ' | 'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'ゎ' |
+        'ャ' | 'ュ' | 'ョ' | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ヮ'
+    )
+}
+
+/// High/low pitch for each mora followed by one extra value for an attached
+/// particle, which shows the difference between heiban and odaka words.
+pub fn pitch_pattern(mora_count: usize, accent: u8) -> Vec<bool> {
+    let accent = accent as usize;
+
+    (1..=mora_count + 1)
+        .map(|i| match accent {
+            0 => i > 1,
+            1 => i == 1,
+            _ => i > 1 && i <= accent,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_kana_join_the_previous_mora() {
+        assert_eq!(morae("きょう"), vec!["きょ", "う"]);
+        assert_eq!(morae("ファイル"), vec!["ファ", "イ", "ル"]);
+    }
+
+    #[test]
+    fn n_sokuon_and_long_vowels_are_morae() {
+        assert_eq!(morae("しんぶん"), vec!["し", "ん", "ぶ", "ん"]);
+        assert_eq!(morae("きって"), vec!["き", "っ", "て"]);
+        assert_eq!(morae("コーヒー"), vec!["コ", "ー", "ヒ", "ー"]);
+        assert!(morae("").is_empty());
+    }
+
+    #[test]
+    fn heiban_and_odaka_differ_on_the_particle() {
+        // はし: 0 (edge), 2 (bridge); the particle is the last value
+        assert_eq!(pitch_pattern(2, 0), vec![false, true, true]);
+        assert_eq!(pitch_pattern(2, 2), vec![false, true, false]);
+    }
+
+    #[test]
+    fn drops_after_the_accented_mora() {
+        assert_eq!(pitch_pattern(3, 1), vec![true, false, false, false]);
+        assert_eq!(pitch_pattern(4, 3), vec![false, true, true, false, false]);
+    }
+}
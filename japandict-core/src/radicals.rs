@@ -0,0 +1,124 @@
+//! Component-based kanji lookup from RADKFILE/KRADFILE
+
+use crate::kanji::stroke_count;
+use crate::data::{tables, Tables};
+use std::collections::BTreeSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Radical {
+    pub literal: char,
+    pub stroke_count: u8,
+}
+
+fn to_chars(code_points: &[u32]) -> impl Iterator<Item = char> + '_ {
+    code_points.iter().filter_map(|&c| char::from_u32(c))
+}
+
+/// All lookup radicals, ordered by stroke count for a picker grid
+pub fn radicals() -> Vec<Radical> {
+    to_chars(tables().radicals)
+        .zip(tables().radical_strokes)
+        .map(|(literal, &stroke_count)| Radical { literal, stroke_count })
+        .collect()
+}
+
+fn kanji_with_radical(t: &Tables, radical: char) -> &'static [u32] {
+    match t.radicals.iter().position(|&r| r == radical as u32) {
+        Some(i) => {
+            let start = t.radical_kanji_offsets[i] as usize;
+            let end = t.radical_kanji_offsets[i + 1] as usize;
+            &t.radical_kanji[start..end]
+        }
+        None => &[],
+    }
+}
+
+/// Code points of the kanji containing every one of `components`, in code
+/// point order
+fn kanji_containing(t: &Tables, components: &[char]) -> Vec<u32> {
+    let Some((first, rest)) = components.split_first() else {
+        return Vec::new();
+    };
+    
+    // Each radical's kanji list is sorted, so intersect against the first
+    let mut matches: Vec<u32> = kanji_with_radical(t, *first).to_vec();
+    for radical in rest {
+        let kanji = kanji_with_radical(t, *radical);
+        matches.retain(|c| kanji.binary_search(c).is_ok());
+    }
+    matches
+}
+
+/// Kanji containing every one of `components`, simplest (fewest strokes) first
+pub fn kanji_by_components(components: &[char]) -> Vec<char> {
+    let mut matches: Vec<char> = to_chars(&kanji_containing(tables(), components)).collect();
+    matches.sort_by_key(|&c| (stroke_count(c).unwrap_or(u8::MAX), c));
+    matches
+}
+
+/// Components of a kanji according to KRADFILE
+pub fn components_of(kanji: char) -> Vec<char> {
+    match tables().krad_literals.binary_search(&(kanji as u32)) {
+        Ok(i) => {
+            let start = tables().krad_component_offsets[i] as usize;
+            let end = tables().krad_component_offsets[i + 1] as usize;
+            to_chars(&tables().krad_components[start..end]).collect()
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Radicals that can still narrow down a selection, i.e. those appearing in
+/// at least one kanji that contains all of `components`. With nothing
+/// selected every radical is compatible.
+pub fn compatible_radicals(components: &[char]) -> BTreeSet<char> {
+    if components.is_empty() {
+        return to_chars(tables().radicals).collect();
+    }
+    
+    kanji_by_components(components)
+        .into_iter()
+        .flat_map(components_of)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leak(values: Vec<u32>) -> &'static [u32] {
+        Box::leak(values.into_boxed_slice())
+    }
+
+    /// 口 is in 右, 品 and 語; 一 in 右 and 語 (sorted by code point, as
+    /// codegen writes them)
+    fn radical_tables() -> Tables {
+        let kanji_with_mouth = vec!['右' as u32, '品' as u32, '語' as u32];
+        let kanji_with_one = vec!['右' as u32, '語' as u32];
+        let offsets = vec![0, kanji_with_mouth.len() as u32, (kanji_with_mouth.len() + kanji_with_one.len()) as u32];
+        Tables {
+            radicals: leak(vec!['口' as u32, '一' as u32]),
+            radical_kanji_offsets: leak(offsets),
+            radical_kanji: leak([kanji_with_mouth, kanji_with_one].concat()),
+            ..Tables::default()
+        }
+    }
+
+    fn containing(components: &[char]) -> Vec<char> {
+        to_chars(&kanji_containing(&radical_tables(), components)).collect()
+    }
+
+    #[test]
+    fn intersects_the_kanji_of_every_component() {
+        assert_eq!(containing(&['口']), ['右', '品', '語']);
+        assert_eq!(containing(&['口', '一']), ['右', '語']);
+        assert_eq!(containing(&['一', '口']), ['右', '語']);
+    }
+
+    #[test]
+    fn finds_nothing_without_components_or_for_unknown_ones() {
+        assert!(containing(&[]).is_empty());
+        assert!(containing(&['木']).is_empty());
+        assert!(containing(&['口', '木']).is_empty());
+    }
+}
@@ -1,122 +1,228 @@
 //! Dictionary search using feature-based scoring
 //! 
-//! 1. Detect query type (kanji/kana/english)
+//! 1. Detect query type (kanji/kana/gloss)
 //! 2. Generate candidates with exact/prefix/fuzzy matching  
 //! 3. Score with weighted features prioritizing common words
 //! 4. Tie-break consistently
 
 use crate::dictionary::*;
-use dictionary_data::WORD_COUNT;
+use crate::names::*;
+use crate::data::tables;
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
 // Global search indices - built once on startup
-static ENGLISH_INDEX: OnceLock<HashMap<String, Vec<usize>>> = OnceLock::new();
+/// Gloss index per language code
+static GLOSS_INDEX: OnceLock<HashMap<&'static str, HashMap<String, Vec<usize>>>> = OnceLock::new();
 static KANJI_INDEX: OnceLock<HashMap<String, Vec<usize>>> = OnceLock::new();
 static KANA_INDEX: OnceLock<HashMap<String, Vec<usize>>> = OnceLock::new();
+/// Sorted by name, so a prefix search only visits the names that match
+static NAME_INDEX: OnceLock<Vec<(String, Vec<usize>)>> = OnceLock::new();
+
+const NAME_RESULT_LIMIT: usize = 20;
+
+/// Number of steps `build_search_indices_with_progress` reports
+pub const SEARCH_INDEX_COUNT: usize = 4;
 
 /// Build complete search indices on startup - much faster than on-demand caching
 pub fn build_search_indices() {
-    #[cfg(not(target_arch = "wasm32"))]
+    build_search_indices_with_progress(|_, _| {});
+}
+
+/// Like `build_search_indices`, calling `progress(done, total)` as indices finish.
+/// On native all indices are built at once, so progress is reported at the end.
+pub fn build_search_indices_with_progress(mut progress: impl FnMut(usize, usize)) {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        // Each index is itself sharded across the rayon pool
+        let ((gloss, kanji), (kana, names)) = rayon::join(
+            || rayon::join(build_gloss_index, build_kanji_index),
+            || rayon::join(build_kana_index, build_name_index),
+        );
+        
+        GLOSS_INDEX.set(gloss).unwrap();
+        KANJI_INDEX.set(kanji).unwrap();
+        KANA_INDEX.set(kana).unwrap();
+        NAME_INDEX.set(names).unwrap();
+        progress(SEARCH_INDEX_COUNT, SEARCH_INDEX_COUNT);
+    }
+    
+    #[cfg(all(not(feature = "parallel"), not(target_arch = "wasm32")))]
     {
         // Build indices in parallel for better performance on native platforms
         std::thread::scope(|s| {
-            let english_handle = s.spawn(|| build_english_index());
-            let kanji_handle = s.spawn(|| build_kanji_index()); 
-            let kana_handle = s.spawn(|| build_kana_index());
+            let gloss_handle = s.spawn(build_gloss_index);
+            let kanji_handle = s.spawn(build_kanji_index);
+            let kana_handle = s.spawn(build_kana_index);
+            let name_handle = s.spawn(build_name_index);
             
-            ENGLISH_INDEX.set(english_handle.join().unwrap()).unwrap();
+            GLOSS_INDEX.set(gloss_handle.join().unwrap()).unwrap();
             KANJI_INDEX.set(kanji_handle.join().unwrap()).unwrap();
             KANA_INDEX.set(kana_handle.join().unwrap()).unwrap();
+            NAME_INDEX.set(name_handle.join().unwrap()).unwrap();
         });
+        progress(SEARCH_INDEX_COUNT, SEARCH_INDEX_COUNT);
     }
     
     #[cfg(target_arch = "wasm32")]
     {
         // Build indices sequentially on WASM since threading is not supported
-        ENGLISH_INDEX.set(build_english_index()).unwrap();
+        GLOSS_INDEX.set(build_gloss_index()).unwrap();
+        progress(1, SEARCH_INDEX_COUNT);
         KANJI_INDEX.set(build_kanji_index()).unwrap();
+        progress(2, SEARCH_INDEX_COUNT);
         KANA_INDEX.set(build_kana_index()).unwrap();
+        progress(3, SEARCH_INDEX_COUNT);
+        NAME_INDEX.set(build_name_index()).unwrap();
+        progress(4, SEARCH_INDEX_COUNT);
     }
 }
 
-fn build_english_index() -> HashMap<String, Vec<usize>> {
-    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+type Index = HashMap<String, Vec<usize>>;
+
+/// Fold entries `0..count` into an index. With the `parallel` feature the range
+/// is split into contiguous shards whose partial indices are merged in order,
+/// so posting lists come out ascending either way.
+fn build_index<T: Default + Send>(
+    count: usize,
+    add: impl Fn(&mut T, usize) + Sync,
+    merge: impl Fn(&mut T, T) + Sync,
+) -> T {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
+        (0..count)
+            .into_par_iter()
+            .fold(T::default, |mut index, idx| {
+                add(&mut index, idx);
+                index
+            })
+            .reduce(T::default, |mut index, shard| {
+                merge(&mut index, shard);
+                index
+            })
+    }
     
-    for idx in 0..WORD_COUNT {
-        let entry = get_word_entry(idx);
-        
-        for english in &entry.english {
-            let normalized = normalize_query(english);
-            
-            // Index the full meaning
-            index.entry(normalized.clone()).or_default().push(idx);
-            
-            // Index individual words within the meaning
-            let words: Vec<&str> = normalized.split_whitespace().collect();
-            for word in words {
-                if word.len() > 1 { // Skip single letters
-                    index.entry(word.to_string()).or_default().push(idx);
-                }
-            }
-            
-            // Index first word of each semicolon-separated meaning
-            let meanings: Vec<&str> = normalized.split(';').collect();
-            for meaning in meanings {
-                let meaning = meaning.trim();
-                if !meaning.is_empty() {
-                    index.entry(meaning.to_string()).or_default().push(idx);
-                    
-                    // Also index first word of the meaning
-                    if let Some(first_word) = meaning.split_whitespace().next() {
-                        if first_word.len() > 1 {
-                            index.entry(first_word.to_string()).or_default().push(idx);
-                        }
-                    }
-                }
-            }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        let _ = merge; // only used to combine shards
+        let mut index = T::default();
+        for idx in 0..count {
+            add(&mut index, idx);
         }
+        index
+    }
+}
+
+fn merge_index(index: &mut Index, shard: Index) {
+    for (key, indices) in shard {
+        index.entry(key).or_default().extend(indices);
     }
+}
+
+fn build_gloss_index() -> HashMap<&'static str, Index> {
+    let mut indices = build_index(tables().word_count, add_glosses, |indices: &mut HashMap<&'static str, Index>, shard| {
+        for (lang, index) in shard {
+            merge_index(indices.entry(lang).or_default(), index);
+        }
+    });
     
     // Deduplicate all vectors
-    for vec in index.values_mut() {
+    for vec in indices.values_mut().flat_map(|index| index.values_mut()) {
         vec.sort_unstable();
         vec.dedup();
     }
     
-    index
+    indices
 }
 
-fn build_kanji_index() -> HashMap<String, Vec<usize>> {
-    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
-    
-    for idx in 0..WORD_COUNT {
-        let entry = get_word_entry(idx);
+fn add_glosses(indices: &mut HashMap<&'static str, Index>, idx: usize) {
+    for (lang, text) in entry_ref(idx).glosses() {
+        let index = indices.entry(lang).or_default();
+        let normalized = normalize_query(text);
         
-        for kanji in &entry.kanji {
-            index.entry(kanji.to_string()).or_default().push(idx);
+        // Index the full meaning
+        index.entry(normalized.clone()).or_default().push(idx);
+        
+        // Index individual words within the meaning
+        let words: Vec<&str> = normalized.split_whitespace().collect();
+        for word in words {
+            if word.chars().count() > 1 { // Skip single letters
+                index.entry(word.to_string()).or_default().push(idx);
+            }
+        }
+        
+        // Index first word of each semicolon-separated meaning
+        let meanings: Vec<&str> = normalized.split(';').collect();
+        for meaning in meanings {
+            let meaning = meaning.trim();
+            if !meaning.is_empty() {
+                index.entry(meaning.to_string()).or_default().push(idx);
+                
+                // Also index first word of the meaning
+                if let Some(first_word) = meaning.split_whitespace().next() {
+                    if first_word.chars().count() > 1 {
+                        index.entry(first_word.to_string()).or_default().push(idx);
+                    }
+                }
+            }
         }
     }
-    
-    index
 }
 
-fn build_kana_index() -> HashMap<String, Vec<usize>> {
-    let mut index: HashMap<String, Vec<usize>> = HashMap::new();
-    
-    for idx in 0..WORD_COUNT {
-        let entry = get_word_entry(idx);
-        
-        for kana in &entry.kana {
+fn build_kanji_index() -> Index {
+    build_index(tables().word_count, |index: &mut Index, idx| {
+        for kanji in entry_ref(idx).kanji() {
+            index.entry(kanji.to_string()).or_default().push(idx);
+        }
+    }, merge_index)
+}
+
+fn build_kana_index() -> Index {
+    build_index(tables().word_count, |index: &mut Index, idx| {
+        for kana in entry_ref(idx).kana() {
             index.entry(kana.to_string()).or_default().push(idx);
         }
+    }, merge_index)
+}
+
+/// Entries with `form` as one of their kanji or kana forms, in entry order.
+/// Empty until `build_search_indices` has run.
+pub(crate) fn entries_with_form(form: &str) -> Vec<usize> {
+    let kanji = KANJI_INDEX.get().and_then(|index| index.get(form));
+    let kana = KANA_INDEX.get().and_then(|index| index.get(form));
+    let mut entries: Vec<usize> = kanji.into_iter().chain(kana).flatten().copied().collect();
+    entries.sort_unstable();
+    entries.dedup();
+    entries
+}
+
+fn build_name_index() -> Vec<(String, Vec<usize>)> {
+    let index = build_index(tables().name_count, |index: &mut Index, idx| {
+        let entry = get_name_entry(idx);
+        
+        for form in entry.kanji.iter().chain(&entry.kana) {
+            index.entry(form.to_string()).or_default().push(idx);
+        }
+        for translation in &entry.translations {
+            index.entry(normalize_query(translation)).or_default().push(idx);
+        }
+    }, merge_index);
+    
+    let mut index: Vec<(String, Vec<usize>)> = index.into_iter().collect();
+    for (_, vec) in index.iter_mut() {
+        vec.sort_unstable();
+        vec.dedup();
     }
+    index.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
     
     index
 }
 
 #[derive(Debug, Clone)]
 pub struct SearchResult {
+    /// Index of the entry, for `get_word_entry`
+    pub index: usize,
     pub entry: WordEntry,
     pub score: f32,
     pub features: Features,
@@ -130,9 +236,9 @@ pub struct Features {
     pub edit_distance: u8,       // edit distance on readings
     pub has_common: bool,        // JMdict common word
     pub shorter_lemma: bool,     // prefer shorter forms
-    pub gloss_hit: bool,         // english definition match
+    pub gloss_hit: bool,         // gloss (definition) match
     pub first_gloss: bool,       // first word in definition
-    pub exact_english: bool,     // query matches exact English word (not compound)
+    pub exact_english: bool,     // query matches an exact gloss word (not compound)
     pub learner_friendly: bool,  // basic form for learners  
     pub simple_form: bool,       // simple basic form vs compound
 }
@@ -175,18 +281,18 @@ fn score_features(features: &Features) -> f32 {
 enum QueryType {
     Kanji,      // contains kanji characters
     Kana,       // all hiragana/katakana
-    English,    // latin letters
+    Gloss,      // anything else: a definition in the chosen gloss language
 }
 
 fn detect_query_type(query: &str) -> QueryType {
     let has_kanji = query.chars().any(|c| {
         // Basic kanji range (there are more, but this covers most)
-        '\u{4E00}' <= c && c <= '\u{9FAF}'
+        ('\u{4E00}'..='\u{9FAF}').contains(&c)
     });
     
     let has_kana = query.chars().any(|c| {
         // Hiragana and katakana ranges
-        ('\u{3040}' <= c && c <= '\u{309F}') || ('\u{30A0}' <= c && c <= '\u{30FF}')
+        ('\u{3040}'..='\u{309F}').contains(&c) || ('\u{30A0}'..='\u{30FF}').contains(&c)
     });
     
     if has_kanji || has_kana {
@@ -196,7 +302,7 @@ fn detect_query_type(query: &str) -> QueryType {
             QueryType::Kana
         }
     } else {
-        QueryType::English
+        QueryType::Gloss
     }
 }
 
@@ -205,15 +311,15 @@ fn normalize_query(query: &str) -> String {
     query.trim().to_lowercase()
 }
 
-fn detect_simple_form(entry: &WordEntry, _query: &str) -> bool {
+fn detect_simple_form(entry: EntryRef, _query: &str) -> bool {
     // For adjectives, prefer i-adjective forms over nouns
-    if entry.pos.iter().any(|p| p.contains("adj-i")) && entry.kanji.iter().any(|k| k.ends_with("い")) {
+    if entry.pos().any(|p| p.contains("adj-i")) && entry.kanji().any(|k| k.ends_with("い")) {
         return true;
     }
     
     // Prefer simple forms (single kanji or short kana) over compounds
-    let has_simple_kanji = entry.kanji.iter().any(|k| k.chars().count() <= 2);
-    let has_simple_kana = entry.kana.iter().any(|k| k.chars().count() <= 4);
+    let has_simple_kanji = entry.kanji().any(|k| k.chars().count() <= 2);
+    let has_simple_kana = entry.kana().any(|k| k.chars().count() <= 4);
     
     has_simple_kanji || has_simple_kana
 }
@@ -241,7 +347,9 @@ fn simple_edit_distance(a: &str, b: &str) -> u8 {
     diff.min(3) // cap at 3
 }
 
-fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Option<SearchResult> {
+/// Score an entry off its packed view; only matching entries are decoded into a `WordEntry`
+fn evaluate_entry(index: usize, query: &str, query_type: &QueryType, lang: &str) -> Option<SearchResult> {
+    let entry = entry_ref(index);
     let normalized_query = normalize_query(query);
     let mut features = Features::default();
     
@@ -250,7 +358,7 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
     match query_type {
         QueryType::Kanji => {
             // Check kanji forms
-            for kanji in &entry.kanji {
+            for kanji in entry.kanji() {
                 if kanji.to_lowercase() == normalized_query {
                     features.exact_form = true;
                     break;
@@ -261,7 +369,7 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
             }
             
             // Also check kana readings for mixed queries
-            for kana in &entry.kana {
+            for kana in entry.kana() {
                 if kana.to_lowercase() == normalized_query {
                     features.exact_reading = true;
                     break;
@@ -271,7 +379,7 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
         
         QueryType::Kana => {
             // Check kana readings
-            for kana in &entry.kana {
+            for kana in entry.kana() {
                 let kana_lower = kana.to_lowercase();
                 if kana_lower == normalized_query {
                     features.exact_reading = true;
@@ -289,14 +397,14 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
             }
         }
         
-        QueryType::English => {
-            // Check English glosses - be more precise about word boundaries
+        QueryType::Gloss => {
+            // Check glosses in the chosen language - be more precise about word boundaries
             let mut is_very_first = true;
-            for english in &entry.english {
-                let english_lower = english.to_lowercase();
+            for gloss in entry.glosses_in(lang) {
+                let gloss_lower = gloss.to_lowercase();
                 
                 // Split by semicolon for separate meanings
-                let meanings: Vec<&str> = english_lower.split(';').collect();
+                let meanings: Vec<&str> = gloss_lower.split(';').collect();
                 
                 for (i, meaning) in meanings.iter().enumerate() {
                     let clean_meaning = meaning.trim();
@@ -380,11 +488,11 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
     }
     
     // Set quality features
-    features.has_common = entry.is_common;
+    features.has_common = entry.is_common();
     
     // Shorter lemma bonus - prefer simpler forms
-    features.shorter_lemma = entry.kanji.iter().any(|k| k.chars().count() <= 2) ||
-                            entry.kana.iter().any(|k| k.chars().count() <= 3);
+    features.shorter_lemma = entry.kanji().any(|k| k.chars().count() <= 2) ||
+                            entry.kana().any(|k| k.chars().count() <= 3);
     
     // Simple form: prefer basic single-concept words
     features.simple_form = detect_simple_form(entry, query);
@@ -393,27 +501,28 @@ fn evaluate_entry(entry: &WordEntry, query: &str, query_type: &QueryType) -> Opt
     
     
     Some(SearchResult {
-        entry: entry.clone(),
+        index,
+        entry: entry.to_word_entry(),
         score,
         features,
     })
 }
 
-fn find_indexed_entries(query: &str, query_type: &QueryType) -> Vec<usize> {
+fn find_indexed_entries(query: &str, query_type: &QueryType, lang: &str) -> Vec<usize> {
     let normalized_query = normalize_query(query);
     let mut candidates = Vec::new();
     
     match query_type {
-        QueryType::English => {
-            if let Some(english_index) = ENGLISH_INDEX.get() {
+        QueryType::Gloss => {
+            if let Some(gloss_index) = GLOSS_INDEX.get().and_then(|indices| indices.get(lang)) {
                 // Exact match first
-                if let Some(indices) = english_index.get(&normalized_query) {
+                if let Some(indices) = gloss_index.get(&normalized_query) {
                     candidates.extend_from_slice(indices);
                 }
                 
                 // Prefix matches if no exact match
                 if candidates.is_empty() {
-                    for (word, indices) in english_index {
+                    for (word, indices) in gloss_index {
                         if word.starts_with(&normalized_query) && word != &normalized_query {
                             candidates.extend_from_slice(indices);
                         }
@@ -451,6 +560,27 @@ fn find_indexed_entries(query: &str, query_type: &QueryType) -> Vec<usize> {
 }
 
 pub fn search_dictionary(query: &str) -> Vec<WordEntry> {
+    search_dictionary_in(query, default_language())
+}
+
+/// Search with non-Japanese queries matched against the glosses in `lang`
+/// (a JMdict language code such as "ger"); Japanese queries are unaffected.
+pub fn search_dictionary_in(query: &str, lang: &str) -> Vec<WordEntry> {
+    ranked_results(query, lang).into_iter().map(|result| result.entry).collect()
+}
+
+/// Like `search_dictionary_in`, but returns entry indices for `get_word_entry`,
+/// for callers that pass results across a thread or worker boundary
+pub fn search_indices_in(query: &str, lang: &str) -> Vec<usize> {
+    ranked_results(query, lang).into_iter().map(|result| result.index).collect()
+}
+
+/// Like `search_dictionary_in`, with each entry's score and matched features
+pub fn search_results_in(query: &str, lang: &str) -> Vec<SearchResult> {
+    ranked_results(query, lang)
+}
+
+fn ranked_results(query: &str, lang: &str) -> Vec<SearchResult> {
     if query.trim().is_empty() {
         return Vec::new();
     }
@@ -458,23 +588,32 @@ pub fn search_dictionary(query: &str) -> Vec<WordEntry> {
     let query_type = detect_query_type(query);
     
     // Try index-based search first for exact/prefix matches
-    let indexed_candidates = find_indexed_entries(query, &query_type);
+    let indexed_candidates = find_indexed_entries(query, &query_type, lang);
     
     let mut results = Vec::with_capacity(200);
     
     if !indexed_candidates.is_empty() {
-        // Process indexed candidates first
+        // Process indexed candidates first; scores are independent, so shard them
+        // across cores when the `parallel` feature is on (order is kept)
+        #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+        {
+            use rayon::prelude::*;
+            results.par_extend(indexed_candidates.par_iter().filter_map(|&idx| {
+                evaluate_entry(idx, query, &query_type, lang)
+            }));
+        }
+        
+        #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
         for &idx in &indexed_candidates {
-            let entry = crate::dictionary::get_word_entry(idx);
-            if let Some(search_result) = evaluate_entry(&entry, query, &query_type) {
+            if let Some(search_result) = evaluate_entry(idx, query, &query_type, lang) {
                 results.push(search_result);
             }
         }
     } else {
-        // Fallback to full scan for fuzzy matches
-        for i in 0..WORD_COUNT.min(5000) { // Limit scan for performance
-            let entry = crate::dictionary::get_word_entry(i);
-            if let Some(search_result) = evaluate_entry(&entry, query, &query_type) {
+        // Fallback to a scan of the common words for fuzzy matches; entries are
+        // in JMdict order, so the flag byte picks them out cheaply
+        for idx in (0..tables().word_count).filter(|&idx| entry_ref(idx).is_common()).take(5000) { // Limit scan for performance
+            if let Some(search_result) = evaluate_entry(idx, query, &query_type, lang) {
                 results.push(search_result);
                 if results.len() >= 200 {
                     break;
@@ -511,8 +650,41 @@ pub fn search_dictionary(query: &str) -> Vec<WordEntry> {
         a_key.cmp(b_key)
     });
     
-    results.into_iter()
-        .take(50)
-        .map(|result| result.entry)
-        .collect()
+    results.truncate(50);
+    results
+}
+
+/// Proper names matching the query: exact matches first, then prefix matches
+/// in name order (so a name comes before its longer variants). Meant to be
+/// shown as a separate group below the word results from `search_dictionary`.
+pub fn search_names(query: &str) -> Vec<NameEntry> {
+    search_name_indices(query).into_iter().map(get_name_entry).collect()
+}
+
+/// Like `search_names`, but returns indices for `get_name_entry`
+pub fn search_name_indices(query: &str) -> Vec<usize> {
+    if query.trim().is_empty() {
+        return Vec::new();
+    }
+    let Some(name_index) = NAME_INDEX.get() else {
+        return Vec::new();
+    };
+    
+    let normalized_query = normalize_query(query);
+    // Names starting with the query sort right from the query itself on
+    let start = name_index.partition_point(|(name, _)| *name < normalized_query);
+    let names = name_index[start..].iter().take_while(|(name, _)| name.starts_with(&normalized_query));
+    
+    // The exact match, if any, is the first of them; stop as soon as the
+    // limit is reached rather than collecting every prefix match
+    let mut matches = Vec::new();
+    for idx in names.flat_map(|(_, indices)| indices.iter().copied()) {
+        if !matches.contains(&idx) {
+            matches.push(idx);
+        }
+        if matches.len() >= NAME_RESULT_LIMIT {
+            break;
+        }
+    }
+    matches
 }
\ No newline at end of file
@@ -0,0 +1,553 @@
+//! Integrity checks for the generated dictionary data
+//!
+//! The readers in `dictionary`, `kanji`, `names` and the other table modules
+//! index the packed arrays without bounds checks beyond slice indexing, so
+//! corrupted or mismatched data shows up as a panic deep inside a search.
+//! `validate()` walks every table once up front instead, and
+//! `Dictionary::from_bytes` runs the same checks before a file can be installed.
+
+use crate::data::{tables, Tables};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The data does not hash to the checksum codegen recorded
+    ChecksumMismatch { expected: u64, actual: u64 },
+    /// A string offset points past the end of its string data
+    StringOffsetOutOfRange { table: &'static str, index: usize, offset: u32 },
+    /// A string runs to the end of the data without a null terminator
+    UnterminatedString { table: &'static str, index: usize },
+    InvalidUtf8 { table: &'static str, index: usize },
+    /// An entry offset points past the end of the entry data
+    EntryOffsetOutOfRange { entry: usize, offset: u32 },
+    /// An entry's fields run past the next entry or the end of the data
+    EntryOverrun { entry: usize },
+    /// An entry refers to a string index that does not exist
+    StringIndexOutOfRange { entry: usize, index: u32 },
+    /// A gloss language byte is not an index into GLOSS_LANGUAGES
+    InvalidLanguage { entry: usize, language: u8 },
+    /// The id lookup table refers to an entry that does not exist
+    IdIndexOutOfRange { position: usize, entry: u32 },
+    /// A table's length does not match the table or count it goes with
+    LengthMismatch { table: &'static str, expected: usize, actual: usize },
+    /// An offset points past the end of the data it indexes, or before the
+    /// previous offset
+    OffsetOutOfRange { table: &'static str, position: usize, offset: u32 },
+    /// A record runs past the next record or the end of the data
+    RecordOverrun { table: &'static str, record: usize },
+    /// A record refers to a string, sentence or entry that does not exist
+    ReferenceOutOfRange { table: &'static str, record: usize, index: u32 },
+    /// A value of a table that lookups binary search is not above the one
+    /// before it
+    Unsorted { table: &'static str, position: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationError::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: expected {:016x}, got {:016x}", expected, actual)
+            }
+            ValidationError::StringOffsetOutOfRange { table, index, offset } => {
+                write!(f, "{} string {} starts at {}, past the end of the string data", table, index, offset)
+            }
+            ValidationError::UnterminatedString { table, index } => {
+                write!(f, "{} string {} is not null-terminated", table, index)
+            }
+            ValidationError::InvalidUtf8 { table, index } => write!(f, "{} string {} is not valid UTF-8", table, index),
+            ValidationError::EntryOffsetOutOfRange { entry, offset } => {
+                write!(f, "entry {} starts at {}, past the end of the entry data", entry, offset)
+            }
+            ValidationError::EntryOverrun { entry } => write!(f, "entry {} runs past its end", entry),
+            ValidationError::StringIndexOutOfRange { entry, index } => {
+                write!(f, "entry {} refers to missing string {}", entry, index)
+            }
+            ValidationError::InvalidLanguage { entry, language } => {
+                write!(f, "entry {} has unknown gloss language {}", entry, language)
+            }
+            ValidationError::IdIndexOutOfRange { position, entry } => {
+                write!(f, "id index {} refers to missing entry {}", position, entry)
+            }
+            ValidationError::LengthMismatch { table, expected, actual } => {
+                write!(f, "{} has {} values, expected {}", table, actual, expected)
+            }
+            ValidationError::OffsetOutOfRange { table, position, offset } => {
+                write!(f, "{} offset {} is out of range ({})", table, position, offset)
+            }
+            ValidationError::RecordOverrun { table, record } => write!(f, "{} record {} runs past its end", table, record),
+            ValidationError::ReferenceOutOfRange { table, record, index } => {
+                write!(f, "{} record {} refers to missing item {}", table, record, index)
+            }
+            ValidationError::Unsorted { table, position } => write!(f, "{} value {} is out of order", table, position),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Same FNV-1a as codegen's `pool::Fnv1a`
+fn fnv1a(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn le_bytes(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// Check the data against its checksum and walk every table, so that none of
+/// the readers can index out of bounds.
+pub fn validate() -> Result<(), ValidationError> {
+    validate_tables(tables())
+}
+
+pub(crate) fn validate_tables(t: &Tables) -> Result<(), ValidationError> {
+    let actual = checksum(t);
+    if actual != t.data_checksum {
+        return Err(ValidationError::ChecksumMismatch { expected: t.data_checksum, actual });
+    }
+
+    validate_words(t)?;
+    validate_details(t)?;
+    validate_kanji(t)?;
+    validate_radicals(t)?;
+    validate_names(t)?;
+    validate_examples(t)?;
+    validate_xrefs(t)
+}
+
+/// Hash of every array and string list table, in the order codegen writes them
+fn checksum(t: &Tables) -> u64 {
+    let languages: Vec<u8> = t.gloss_languages.iter().flat_map(|lang| lang.bytes().chain([0])).collect();
+    fnv1a(&[
+        t.jmdict_strings,
+        t.jmdict_entries,
+        &le_bytes(t.jmdict_entry_offsets),
+        &le_bytes(t.jmdict_string_offsets),
+        &le_bytes(t.jmdict_id_index),
+        &le_bytes(t.entry_details),
+        &le_bytes(t.entry_detail_offsets),
+        &languages,
+        t.kanjidic_strings,
+        &le_bytes(t.kanjidic_string_offsets),
+        t.kanjidic_entries,
+        &le_bytes(t.kanjidic_entry_offsets),
+        &le_bytes(t.kanjidic_literals),
+        &le_bytes(t.radicals),
+        t.radical_strokes,
+        &le_bytes(t.radical_kanji_offsets),
+        &le_bytes(t.radical_kanji),
+        &le_bytes(t.krad_literals),
+        &le_bytes(t.krad_component_offsets),
+        &le_bytes(t.krad_components),
+        t.names_strings,
+        &le_bytes(t.names_string_offsets),
+        t.names_entries,
+        &le_bytes(t.names_entry_offsets),
+        t.example_strings,
+        &le_bytes(t.example_string_offsets),
+        &le_bytes(t.example_sentences),
+        &le_bytes(t.example_entry_ids),
+        &le_bytes(t.example_link_offsets),
+        &le_bytes(t.example_links),
+        &le_bytes(t.xref_entry_ids),
+        &le_bytes(t.xref_link_offsets),
+        &le_bytes(t.xref_links),
+    ])
+}
+
+fn validate_words(t: &Tables) -> Result<(), ValidationError> {
+    check_strings("JMDICT_STRINGS", t.jmdict_strings, t.jmdict_string_offsets)?;
+    check_count("WORD_COUNT", t.jmdict_entry_offsets.len(), t.word_count)?;
+
+    for entry in 0..t.jmdict_entry_offsets.len() {
+        check_entry(t, entry)?;
+    }
+
+    for (position, &entry) in t.jmdict_id_index.iter().enumerate() {
+        if entry as usize >= t.jmdict_entry_offsets.len() {
+            return Err(ValidationError::IdIndexOutOfRange { position, entry });
+        }
+    }
+
+    Ok(())
+}
+
+/// Bounds-check a single entry and check every string it refers to.
+/// Panics if `entry` itself is out of range.
+pub(crate) fn check_entry(t: &Tables, entry: usize) -> Result<(), ValidationError> {
+    let offset = t.jmdict_entry_offsets[entry];
+    let end = t.jmdict_entry_offsets.get(entry + 1).map_or(t.jmdict_entries.len(), |&next| next as usize);
+    let data = t.jmdict_entries
+        .get(offset as usize..end)
+        .ok_or(ValidationError::EntryOffsetOutOfRange { entry, offset })?;
+    validate_entry(t, entry, data)
+}
+
+fn validate_entry(t: &Tables, entry: usize, data: &[u8]) -> Result<(), ValidationError> {
+    let overrun = ValidationError::EntryOverrun { entry };
+    let bytes = |start: usize, len: usize| data.get(start..start + len).ok_or(overrun.clone());
+
+    let header = bytes(0, 13)?;
+    let counts: Vec<usize> = header[4..12]
+        .chunks(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]) as usize)
+        .collect();
+    let (kana_count, gloss_count) = (counts[1], counts[2]);
+    let index_count: usize = counts.iter().sum();
+
+    // The id index comes first, then every kanji, kana, gloss and POS index
+    let mut string_indices = vec![u32::from_le_bytes([header[0], header[1], header[2], header[3]])];
+    for chunk in bytes(13, index_count * 4)?.chunks(4) {
+        string_indices.push(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+    }
+    for &index in &string_indices {
+        let offset = *t.jmdict_string_offsets
+            .get(index as usize)
+            .ok_or(ValidationError::StringIndexOutOfRange { entry, index })?;
+        check_string("JMDICT_STRINGS", t.jmdict_strings, index as usize, offset)?;
+    }
+
+    let mut pos = 13 + index_count * 4;
+    for _ in 0..kana_count {
+        let accent_count = bytes(pos, 1)?[0] as usize;
+        pos += 1 + bytes(pos + 1, accent_count)?.len();
+    }
+
+    if let Some(&language) = bytes(pos, gloss_count)?.iter().find(|&&lang| lang as usize >= t.gloss_languages.len()) {
+        return Err(ValidationError::InvalidLanguage { entry, language });
+    }
+    // One sense index per gloss follows the languages
+    bytes(pos + gloss_count, gloss_count)?;
+
+    Ok(())
+}
+
+/// Each entry's run of ENTRY_DETAILS: per form a common flag, tag and
+/// priority counts and their strings, then per sense five counts and their
+/// strings
+fn validate_details(t: &Tables) -> Result<(), ValidationError> {
+    const TABLE: &str = "ENTRY_DETAILS";
+    let offsets = t.entry_detail_offsets;
+    check_offsets("ENTRY_DETAIL_OFFSETS", offsets, t.jmdict_entry_offsets.len(), t.entry_details.len())?;
+
+    for entry in 0..offsets.len().saturating_sub(1) {
+        let overrun = ValidationError::RecordOverrun { table: TABLE, record: entry };
+        let mut run = &t.entry_details[offsets[entry] as usize..offsets[entry + 1] as usize];
+
+        // Counts come from the entry header, checked by `validate_words`
+        let data = &t.jmdict_entries[t.jmdict_entry_offsets[entry] as usize..];
+        let form_count = u16::from_le_bytes([data[4], data[5]]) as usize + u16::from_le_bytes([data[6], data[7]]) as usize;
+        for _ in 0..form_count {
+            let header = take(&mut run, 3).ok_or(overrun.clone())?;
+            let strings = take(&mut run, total(&header[1..])).ok_or(overrun.clone())?;
+            check_references(TABLE, entry, strings.iter().copied(), t.jmdict_string_offsets.len())?;
+        }
+        while !run.is_empty() {
+            let counts = take(&mut run, 5).ok_or(overrun.clone())?;
+            let strings = take(&mut run, total(counts)).ok_or(overrun.clone())?;
+            check_references(TABLE, entry, strings.iter().copied(), t.jmdict_string_offsets.len())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// KANJIDIC entries: stroke count, grade, JLPT level, frequency (u16) and
+/// radical, then on, kun and meaning counts and their string indices
+fn validate_kanji(t: &Tables) -> Result<(), ValidationError> {
+    const TABLE: &str = "KANJIDIC_ENTRIES";
+    check_strings("KANJIDIC_STRINGS", t.kanjidic_strings, t.kanjidic_string_offsets)?;
+    check_count("KANJIDIC_ENTRY_OFFSETS", t.kanjidic_literals.len(), t.kanjidic_entry_offsets.len())?;
+    check_count("KANJIDIC_COUNT", t.kanjidic_literals.len(), t.kanjidic_count)?;
+
+    for kanji in 0..t.kanjidic_entry_offsets.len() {
+        let mut data = record("KANJIDIC_ENTRY_OFFSETS", t.kanjidic_entries, t.kanjidic_entry_offsets, kanji)?;
+        let overrun = ValidationError::RecordOverrun { table: TABLE, record: kanji };
+        let header = take(&mut data, 9).ok_or(overrun.clone())?;
+        let count = header[6..9].iter().map(|&count| count as usize).sum::<usize>();
+        let indices = take(&mut data, count * 4).ok_or(overrun)?;
+        check_references(TABLE, kanji, u32_values(indices), t.kanjidic_string_offsets.len())?;
+    }
+
+    Ok(())
+}
+
+fn validate_radicals(t: &Tables) -> Result<(), ValidationError> {
+    check_count("RADICAL_STROKES", t.radicals.len(), t.radical_strokes.len())?;
+    check_offsets("RADICAL_KANJI_OFFSETS", t.radical_kanji_offsets, t.radicals.len(), t.radical_kanji.len())?;
+    check_offsets("KRAD_COMPONENT_OFFSETS", t.krad_component_offsets, t.krad_literals.len(), t.krad_components.len())?;
+
+    // kanji_by_components binary searches each radical's kanji, and
+    // components_of the KRADFILE literals
+    for span in t.radical_kanji_offsets.windows(2) {
+        let (start, end) = (span[0] as usize, span[1] as usize);
+        check_sorted("RADICAL_KANJI", start, &t.radical_kanji[start..end])?;
+    }
+    check_sorted("KRAD_LITERALS", 0, t.krad_literals)
+}
+
+/// JMnedict entries: id string index, kanji, kana, translation and name type
+/// counts (a byte each), then their string indices
+fn validate_names(t: &Tables) -> Result<(), ValidationError> {
+    const TABLE: &str = "NAMES_ENTRIES";
+    check_strings("NAMES_STRINGS", t.names_strings, t.names_string_offsets)?;
+    check_count("NAME_COUNT", t.names_entry_offsets.len(), t.name_count)?;
+
+    for name in 0..t.names_entry_offsets.len() {
+        let mut data = record("NAMES_ENTRY_OFFSETS", t.names_entries, t.names_entry_offsets, name)?;
+        let overrun = ValidationError::RecordOverrun { table: TABLE, record: name };
+        let header = take(&mut data, 8).ok_or(overrun.clone())?;
+        let count = header[4..8].iter().map(|&count| count as usize).sum::<usize>();
+        let indices = take(&mut data, count * 4).ok_or(overrun)?;
+        check_references(TABLE, name, u32_values(&header[..4]).chain(u32_values(indices)), t.names_string_offsets.len())?;
+    }
+
+    Ok(())
+}
+
+/// Sentences are (Tatoeba id, Japanese, English) triples and links are
+/// (sentence, sense, highlight) triples
+fn validate_examples(t: &Tables) -> Result<(), ValidationError> {
+    check_strings("EXAMPLE_STRINGS", t.example_strings, t.example_string_offsets)?;
+    check_count("EXAMPLE_SENTENCES", t.example_sentences.len() / 3 * 3, t.example_sentences.len())?;
+    check_count("EXAMPLE_LINKS", t.example_links.len() / 3 * 3, t.example_links.len())?;
+    check_offsets("EXAMPLE_LINK_OFFSETS", t.example_link_offsets, t.example_entry_ids.len(), t.example_links.len() / 3)?;
+
+    let string_count = t.example_string_offsets.len();
+    for (sentence, values) in t.example_sentences.chunks_exact(3).enumerate() {
+        check_references("EXAMPLE_SENTENCES", sentence, values[1..].iter().copied(), string_count)?;
+    }
+    let sentence_count = t.example_sentences.len() / 3;
+    for (link, values) in t.example_links.chunks_exact(3).enumerate() {
+        check_references("EXAMPLE_LINKS", link, [values[0]].into_iter(), sentence_count)?;
+        check_references("EXAMPLE_LINKS", link, [values[2]].into_iter(), string_count)?;
+    }
+
+    Ok(())
+}
+
+/// Links are (JMdict id, kind) pairs; the ids are resolved at lookup time
+fn validate_xrefs(t: &Tables) -> Result<(), ValidationError> {
+    check_offsets("XREF_LINK_OFFSETS", t.xref_link_offsets, t.xref_entry_ids.len(), t.xref_links.len() / 2)
+}
+
+fn check_strings(table: &'static str, strings: &[u8], offsets: &[u32]) -> Result<(), ValidationError> {
+    for (index, &offset) in offsets.iter().enumerate() {
+        check_string(table, strings, index, offset)?;
+    }
+    Ok(())
+}
+
+/// Check that a string starts in range, is null-terminated and is UTF-8
+pub(crate) fn check_string(table: &'static str, strings: &[u8], index: usize, offset: u32) -> Result<(), ValidationError> {
+    let rest = strings
+        .get(offset as usize..)
+        .ok_or(ValidationError::StringOffsetOutOfRange { table, index, offset })?;
+    let end = rest.iter().position(|&b| b == 0).ok_or(ValidationError::UnterminatedString { table, index })?;
+    std::str::from_utf8(&rest[..end]).map_err(|_| ValidationError::InvalidUtf8 { table, index })?;
+    Ok(())
+}
+
+fn check_count(table: &'static str, expected: usize, actual: usize) -> Result<(), ValidationError> {
+    if expected != actual {
+        return Err(ValidationError::LengthMismatch { table, expected, actual });
+    }
+    Ok(())
+}
+
+/// Check that `values`, found at `start` in `table`, are strictly ascending
+fn check_sorted(table: &'static str, start: usize, values: &[u32]) -> Result<(), ValidationError> {
+    match values.windows(2).position(|pair| pair[0] >= pair[1]) {
+        Some(i) => Err(ValidationError::Unsorted { table, position: start + i + 1 }),
+        None => Ok(()),
+    }
+}
+
+/// Check a table of `count + 1` ascending offsets into `len` values, where
+/// record `i` spans `offsets[i]..offsets[i + 1]`. Empty when there are no
+/// records.
+fn check_offsets(table: &'static str, offsets: &[u32], count: usize, len: usize) -> Result<(), ValidationError> {
+    if count == 0 && offsets.is_empty() {
+        return Ok(());
+    }
+    check_count(table, count + 1, offsets.len())?;
+
+    let mut previous = 0;
+    for (position, &offset) in offsets.iter().enumerate() {
+        if offset < previous || offset as usize > len {
+            return Err(ValidationError::OffsetOutOfRange { table, position, offset });
+        }
+        previous = offset;
+    }
+    Ok(())
+}
+
+/// The bytes of record `index` in a table of start offsets, up to the next
+/// record or the end of the data
+fn record<'a>(table: &'static str, data: &'a [u8], offsets: &[u32], index: usize) -> Result<&'a [u8], ValidationError> {
+    let offset = offsets[index];
+    let end = offsets.get(index + 1).map_or(data.len(), |&next| next as usize);
+    data.get(offset as usize..end).ok_or(ValidationError::OffsetOutOfRange { table, position: index, offset })
+}
+
+fn check_references(
+    table: &'static str,
+    record: usize,
+    indices: impl Iterator<Item = u32>,
+    count: usize,
+) -> Result<(), ValidationError> {
+    for index in indices {
+        if index as usize >= count {
+            return Err(ValidationError::ReferenceOutOfRange { table, record, index });
+        }
+    }
+    Ok(())
+}
+
+/// Split the first `len` values off `rest`
+fn take<'a, T>(rest: &mut &'a [T], len: usize) -> Option<&'a [T]> {
+    if len > rest.len() {
+        return None;
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Some(head)
+}
+
+/// Sum of packed counts, saturating so that corrupt counts overrun instead of
+/// overflowing
+fn total(counts: &[u32]) -> usize {
+    counts.iter().fold(0usize, |sum, &count| sum.saturating_add(count as usize))
+}
+
+fn u32_values(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    bytes.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::tests::sample;
+
+    fn sample_tables() -> Tables {
+        crate::data::parse_tables(Box::leak(sample().into_boxed_slice())).unwrap()
+    }
+
+    fn leak<T>(values: Vec<T>) -> &'static [T] {
+        Box::leak(values.into_boxed_slice())
+    }
+
+    /// Validate after a change, with the checksum updated to match so the
+    /// structural checks are what fails
+    fn validate_changed(change: impl FnOnce(&mut Tables)) -> Result<(), ValidationError> {
+        let mut t = sample_tables();
+        change(&mut t);
+        t.data_checksum = checksum(&t);
+        validate_tables(&t)
+    }
+
+    #[test]
+    fn accepts_codegen_output() {
+        assert_eq!(validate_tables(&sample_tables()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_checksum_mismatch() {
+        let mut t = sample_tables();
+        t.jmdict_id_index = leak(vec![0, 0]);
+        assert!(matches!(validate_tables(&t), Err(ValidationError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn rejects_bad_strings() {
+        let result = validate_changed(|t| {
+            let mut strings = t.jmdict_strings.to_vec();
+            strings[0] = 0xff;
+            t.jmdict_strings = leak(strings);
+        });
+        assert_eq!(result, Err(ValidationError::InvalidUtf8 { table: "JMDICT_STRINGS", index: 0 }));
+
+        let result = validate_changed(|t| t.jmdict_strings = &t.jmdict_strings[..t.jmdict_strings.len() - 1]);
+        assert_eq!(result, Err(ValidationError::UnterminatedString { table: "JMDICT_STRINGS", index: 4 }));
+    }
+
+    #[test]
+    fn rejects_truncated_entries() {
+        let result = validate_changed(|t| t.jmdict_entries = &t.jmdict_entries[..t.jmdict_entries.len() - 1]);
+        assert_eq!(result, Err(ValidationError::EntryOverrun { entry: 0 }));
+
+        let result = validate_changed(|t| t.jmdict_entry_offsets = leak(vec![100]));
+        assert_eq!(result, Err(ValidationError::EntryOffsetOutOfRange { entry: 0, offset: 100 }));
+    }
+
+    #[test]
+    fn rejects_bad_references() {
+        let result = validate_changed(|t| t.jmdict_id_index = leak(vec![1]));
+        assert_eq!(result, Err(ValidationError::IdIndexOutOfRange { position: 0, entry: 1 }));
+
+        let result = validate_changed(|t| {
+            let mut details = t.entry_details.to_vec();
+            *details.last_mut().unwrap() = 99;
+            t.entry_details = leak(details);
+        });
+        assert_eq!(result, Err(ValidationError::ReferenceOutOfRange { table: "ENTRY_DETAILS", record: 0, index: 99 }));
+    }
+
+    #[test]
+    fn rejects_inconsistent_tables() {
+        let result = validate_changed(|t| t.word_count = 2);
+        assert_eq!(result, Err(ValidationError::LengthMismatch { table: "WORD_COUNT", expected: 1, actual: 2 }));
+
+        let result = validate_changed(|t| t.entry_detail_offsets = leak(vec![0, 13]));
+        assert_eq!(result, Err(ValidationError::OffsetOutOfRange { table: "ENTRY_DETAIL_OFFSETS", position: 1, offset: 13 }));
+
+        // A sense claiming two parts of speech runs past the entry's details
+        let result = validate_changed(|t| {
+            let mut details = t.entry_details.to_vec();
+            details[6] = 2;
+            t.entry_details = leak(details);
+        });
+        assert_eq!(result, Err(ValidationError::RecordOverrun { table: "ENTRY_DETAILS", record: 0 }));
+
+        let result = validate_changed(|t| t.xref_entry_ids = leak(vec![1000000]));
+        assert_eq!(result, Err(ValidationError::LengthMismatch { table: "XREF_LINK_OFFSETS", expected: 2, actual: 0 }));
+    }
+
+    #[test]
+    fn rejects_partial_example_triples() {
+        let result = validate_changed(|t| t.example_sentences = leak(vec![1, 0, 0, 2]));
+        assert_eq!(result, Err(ValidationError::LengthMismatch { table: "EXAMPLE_SENTENCES", expected: 3, actual: 4 }));
+
+        let result = validate_changed(|t| t.example_links = leak(vec![0, 0]));
+        assert_eq!(result, Err(ValidationError::LengthMismatch { table: "EXAMPLE_LINKS", expected: 0, actual: 2 }));
+    }
+
+    #[test]
+    fn rejects_unsorted_lookup_tables() {
+        let with_radicals = |kanji: Vec<u32>| {
+            move |t: &mut Tables| {
+                t.radicals = leak(vec!['口' as u32, '一' as u32]);
+                t.radical_strokes = leak(vec![3, 1]);
+                t.radical_kanji_offsets = leak(vec![0, 2, kanji.len() as u32]);
+                t.radical_kanji = leak(kanji);
+            }
+        };
+        // Each radical's kanji are sorted on their own
+        let sorted = vec!['品' as u32, '語' as u32, '右' as u32, '語' as u32];
+        assert_eq!(validate_changed(with_radicals(sorted)), Ok(()));
+
+        let unsorted = vec!['品' as u32, '語' as u32, '語' as u32, '右' as u32];
+        assert_eq!(validate_changed(with_radicals(unsorted)), Err(ValidationError::Unsorted { table: "RADICAL_KANJI", position: 3 }));
+
+        let result = validate_changed(|t| {
+            t.krad_literals = leak(vec!['語' as u32, '右' as u32]);
+            t.krad_component_offsets = leak(vec![0, 0, 0]);
+        });
+        assert_eq!(result, Err(ValidationError::Unsorted { table: "KRAD_LITERALS", position: 1 }));
+    }
+}
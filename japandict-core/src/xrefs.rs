@@ -0,0 +1,36 @@
+//! Cross-references between word entries (related words and antonyms)
+
+use crate::data::tables;
+use crate::dictionary::index_of_jmdict_id;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XrefKind {
+    Related,
+    Antonym,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrossReference {
+    pub kind: XrefKind,
+    /// Index of the referenced entry, for `get_word_entry`
+    pub index: usize,
+}
+
+/// Words a JMdict entry id refers to, in source order
+pub fn cross_references(entry_id: &str) -> Vec<CrossReference> {
+    let Some(position) = entry_id.parse::<u32>().ok().and_then(|id| tables().xref_entry_ids.binary_search(&id).ok()) else {
+        return Vec::new();
+    };
+
+    let start = tables().xref_link_offsets[position] as usize * 2;
+    let end = tables().xref_link_offsets[position + 1] as usize * 2;
+
+    tables().xref_links[start..end]
+        .chunks_exact(2)
+        .filter_map(|link| {
+            let index = index_of_jmdict_id(&link[0].to_string()).ok()?;
+            let kind = if link[1] == 1 { XrefKind::Antonym } else { XrefKind::Related };
+            Some(CrossReference { kind, index })
+        })
+        .collect()
+}
@@ -0,0 +1,407 @@
+//! Request routing and the JSON shapes of the responses
+//!
+//! - `GET /search?q=...&lang=...&limit=...`: ranked word entries
+//! - `GET /entry/{jmdict-id}`: one entry with every sense, examples and cross-references
+//! - `GET /kanji/{char}`: KANJIDIC2 information and components of a kanji
+//! - `GET /parse?text=...` or `POST /parse` with the text as the body: the
+//!   text split into dictionary words
+
+use japandict_core::{
+    components_of, cross_references, examples_for, get_by_jmdict_id, get_kanji, get_word_entry, languages,
+    parse_text, search_indices_in, EntryError, WordEntry, XrefKind,
+};
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 50;
+
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(value: &impl Serialize) -> Response {
+        Response { status: 200, body: serde_json::to_string(value).expect("Failed to serialize response") }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Response {
+        Response::json(&ErrorBody { error: message.into() }).with_status(status)
+    }
+
+    fn with_status(self, status: u16) -> Response {
+        Response { status, ..self }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// An entry as listed in search and parse results
+#[derive(Serialize)]
+struct EntrySummary {
+    id: &'static str,
+    kanji: Vec<&'static str>,
+    kana: Vec<&'static str>,
+    common: bool,
+    pos: Vec<&'static str>,
+    /// Glosses in the requested language, grouped by sense
+    senses: Vec<Vec<&'static str>>,
+}
+
+impl EntrySummary {
+    fn new(entry: WordEntry, lang: &str) -> EntrySummary {
+        EntrySummary {
+            senses: entry.senses_in(lang),
+            id: entry.id,
+            kanji: entry.kanji,
+            kana: entry.kana,
+            common: entry.is_common,
+            pos: entry.pos,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchBody {
+    query: String,
+    lang: String,
+    results: Vec<EntrySummary>,
+}
+
+#[derive(Serialize)]
+struct EntryBody {
+    #[serde(flatten)]
+    summary: EntrySummary,
+    /// Pitch accent downstep positions for each reading in `kana`
+    accents: Vec<Vec<u8>>,
+    examples: Vec<ExampleBody>,
+    cross_references: Vec<CrossReferenceBody>,
+}
+
+#[derive(Serialize)]
+struct ExampleBody {
+    id: u32,
+    japanese: &'static str,
+    english: &'static str,
+    sense: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct CrossReferenceBody {
+    kind: &'static str,
+    id: &'static str,
+    kanji: Vec<&'static str>,
+    kana: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct KanjiBody {
+    literal: char,
+    on_readings: Vec<&'static str>,
+    kun_readings: Vec<&'static str>,
+    meanings: Vec<&'static str>,
+    stroke_count: u8,
+    grade: Option<u8>,
+    jlpt: Option<u8>,
+    frequency: Option<u16>,
+    radical: u8,
+    components: Vec<char>,
+}
+
+#[derive(Serialize)]
+struct ParseBody<'a> {
+    tokens: Vec<TokenBody<'a>>,
+}
+
+#[derive(Serialize)]
+struct TokenBody<'a> {
+    text: &'a str,
+    /// Byte offset of `text` in the request text
+    start: usize,
+    entries: Vec<EntrySummary>,
+}
+
+/// Why a request body could not be read
+#[derive(Debug)]
+pub enum BodyError {
+    /// The body is longer than the server accepts
+    TooLarge { limit: u64 },
+    Io(std::io::Error),
+}
+
+/// Answer one request. `url` is the path with its query string; `body` is the
+/// request body, read only for `POST /parse`.
+pub fn handle(method: &str, url: &str, body: impl FnOnce() -> Result<String, BodyError>, default_lang: &str) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = parse_query(query);
+    let lang = params.get("lang").map_or(default_lang, String::as_str);
+    if !languages().contains(&lang) {
+        return Response::error(400, format!("Unknown language '{}' (available: {})", lang, languages().join(", ")));
+    }
+
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["search"]) => search(&params, lang),
+        ("GET", ["entry", id]) => entry(&decode(id), lang),
+        ("GET", ["kanji", literal]) => kanji(&decode(literal)),
+        ("GET", ["parse"]) => parse(params.get("text").map_or("", String::as_str), lang),
+        ("POST", ["parse"]) => match body() {
+            Ok(text) => parse(&text, lang),
+            Err(BodyError::TooLarge { limit }) => {
+                Response::error(413, format!("The request body is larger than {} bytes", limit))
+            }
+            Err(BodyError::Io(err)) => Response::error(400, format!("Failed to read the request body: {}", err)),
+        },
+        (_, ["search"] | ["entry", _] | ["kanji", _] | ["parse"]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+fn search(params: &HashMap<String, String>, lang: &str) -> Response {
+    let Some(query) = params.get("q").filter(|q| !q.trim().is_empty()) else {
+        return Response::error(400, "Missing query parameter 'q'");
+    };
+    let limit = match params.get("limit").map(|limit| limit.parse::<usize>()) {
+        None => DEFAULT_SEARCH_LIMIT,
+        Some(Ok(limit)) => limit.min(MAX_SEARCH_LIMIT),
+        Some(Err(_)) => return Response::error(400, "'limit' must be a number"),
+    };
+
+    let results = search_indices_in(query, lang)
+        .into_iter()
+        .take(limit)
+        .map(|index| EntrySummary::new(get_word_entry(index), lang))
+        .collect();
+    Response::json(&SearchBody { query: query.clone(), lang: lang.to_string(), results })
+}
+
+fn entry(id: &str, lang: &str) -> Response {
+    let entry = match get_by_jmdict_id(id) {
+        Ok(entry) => entry,
+        Err(err @ EntryError::UnknownId(_)) => return Response::error(404, err.to_string()),
+        Err(err) => return Response::error(500, err.to_string()),
+    };
+
+    let examples = examples_for(entry.id)
+        .into_iter()
+        .map(|example| ExampleBody {
+            id: example.id,
+            japanese: example.japanese,
+            english: example.english,
+            sense: example.sense,
+        })
+        .collect();
+    let cross_references = cross_references(entry.id)
+        .into_iter()
+        .map(|xref| {
+            let target = get_word_entry(xref.index);
+            CrossReferenceBody {
+                kind: match xref.kind {
+                    XrefKind::Related => "related",
+                    XrefKind::Antonym => "antonym",
+                },
+                id: target.id,
+                kanji: target.kanji,
+                kana: target.kana,
+            }
+        })
+        .collect();
+
+    Response::json(&EntryBody {
+        accents: entry.accents.clone(),
+        summary: EntrySummary::new(entry, lang),
+        examples,
+        cross_references,
+    })
+}
+
+fn kanji(literal: &str) -> Response {
+    let mut chars = literal.chars();
+    let (Some(literal), None) = (chars.next(), chars.next()) else {
+        return Response::error(400, "Expected a single kanji");
+    };
+    let Some(info) = get_kanji(literal) else {
+        return Response::error(404, format!("No KANJIDIC2 entry for {}", literal));
+    };
+
+    Response::json(&KanjiBody {
+        components: components_of(info.literal),
+        literal: info.literal,
+        on_readings: info.on_readings,
+        kun_readings: info.kun_readings,
+        meanings: info.meanings,
+        stroke_count: info.stroke_count,
+        grade: info.grade,
+        jlpt: info.jlpt,
+        frequency: info.frequency,
+        radical: info.radical,
+    })
+}
+
+fn parse(text: &str, lang: &str) -> Response {
+    let tokens = parse_text(text)
+        .into_iter()
+        .map(|token| TokenBody {
+            text: token.text,
+            start: token.start,
+            entries: token.entries.into_iter().map(|index| EntrySummary::new(get_word_entry(index), lang)).collect(),
+        })
+        .collect();
+    Response::json(&ParseBody { tokens })
+}
+
+/// Decode `application/x-www-form-urlencoded` query parameters
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(&key.replace('+', " ")), decode(&value.replace('+', " ")))
+        })
+        .collect()
+}
+
+fn decode(text: &str) -> String {
+    percent_decode_str(text).decode_utf8_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use japandict_core::{build_search_indices, Dictionary};
+    use jmdict_codegen::sample;
+    use serde_json::Value;
+    use std::sync::Once;
+
+    /// Install a small dictionary once for every test: dog and cat in English
+    /// and German, a hot dog, and 60 words glossed "test" for the limit
+    fn setup() {
+        static SETUP: Once = Once::new();
+        SETUP.call_once(|| {
+            let mut words = vec![
+                sample::word("1000000", Some("犬"), "いぬ", &[("eng", "dog"), ("ger", "Hund")]),
+                sample::word("1000010", Some("猫"), "ねこ", &[("eng", "cat"), ("ger", "Katze")]),
+                sample::word("1000020", None, "ホットドッグ", &[("eng", "hot dog")]),
+            ];
+            for i in 0..60 {
+                words.push(sample::word(&format!("20000{:02}", i), None, &format!("てすと{}", i), &[("eng", "test")]));
+            }
+            Dictionary::from_bytes(sample::dictionary(&words)).unwrap().install().unwrap();
+            build_search_indices();
+        });
+    }
+
+    fn request(method: &str, url: &str, body: Result<String, BodyError>) -> (u16, Value) {
+        setup();
+        let response = handle(method, url, || body, "eng");
+        (response.status, serde_json::from_str(&response.body).unwrap())
+    }
+
+    fn get(url: &str) -> (u16, Value) {
+        request("GET", url, Ok(String::new()))
+    }
+
+    fn ids(results: &Value) -> Vec<&str> {
+        results.as_array().unwrap().iter().map(|entry| entry["id"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn searches_in_the_requested_language() {
+        let (status, body) = get("/search?q=dog");
+        assert_eq!(status, 200);
+        assert_eq!(body["lang"], "eng");
+        assert_eq!(ids(&body["results"])[0], "1000000");
+        assert_eq!(body["results"][0]["senses"], serde_json::json!([["dog"]]));
+
+        let (status, body) = get("/search?q=Hund&lang=ger");
+        assert_eq!(status, 200);
+        assert_eq!(ids(&body["results"]), ["1000000"]);
+        assert_eq!(body["results"][0]["senses"], serde_json::json!([["Hund"]]));
+
+        let (status, body) = get("/search?q=dog&lang=fre");
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("'fre'"));
+    }
+
+    #[test]
+    fn clamps_the_search_limit() {
+        let count = |url: &str| get(url).1["results"].as_array().unwrap().len();
+        assert_eq!(count("/search?q=test"), DEFAULT_SEARCH_LIMIT);
+        assert_eq!(count("/search?q=test&limit=3"), 3);
+        assert_eq!(count("/search?q=test&limit=1000"), MAX_SEARCH_LIMIT);
+
+        assert_eq!(get("/search?q=test&limit=many").0, 400);
+        assert_eq!(get("/search").0, 400);
+        assert_eq!(get("/search?q=+").0, 400);
+    }
+
+    #[test]
+    fn decodes_query_parameters() {
+        let params = parse_query("q=hot+dog&text=%E7%8A%AC&flag&");
+        assert_eq!(params["q"], "hot dog");
+        assert_eq!(params["text"], "犬");
+        assert_eq!(params["flag"], "");
+        assert_eq!(params.len(), 3);
+
+        let (_, body) = get("/search?q=hot+dog");
+        assert_eq!(body["query"], "hot dog");
+        assert_eq!(ids(&body["results"])[0], "1000020");
+    }
+
+    #[test]
+    fn looks_up_entries_by_id() {
+        let (status, body) = get("/entry/1000010");
+        assert_eq!(status, 200);
+        assert_eq!(body["kanji"], serde_json::json!(["猫"]));
+        assert_eq!(body["accents"], serde_json::json!([[0]]));
+
+        assert_eq!(get("/entry/999").0, 404);
+    }
+
+    #[test]
+    fn takes_a_single_kanji() {
+        assert_eq!(get("/kanji/%E7%8A%AC%E7%8C%AB").0, 400);
+        // The sample has no KANJIDIC2 data
+        let (status, body) = get("/kanji/%E7%8A%AC");
+        assert_eq!(status, 404);
+        assert_eq!(body["error"], "No KANJIDIC2 entry for 犬");
+    }
+
+    #[test]
+    fn parses_text_from_the_query_or_the_body() {
+        let (status, body) = get("/parse?text=%E7%8A%AC%E3%81%A8%E7%8C%AB");
+        assert_eq!(status, 200);
+        let tokens = body["tokens"].as_array().unwrap();
+        assert_eq!(tokens[0]["text"], "犬");
+        assert_eq!(ids(&tokens[0]["entries"]), ["1000000"]);
+
+        let (status, body) = request("POST", "/parse", Ok("猫".to_string()));
+        assert_eq!(status, 200);
+        assert_eq!(ids(&body["tokens"][0]["entries"]), ["1000010"]);
+
+        let (status, body) = request("POST", "/parse", Err(BodyError::TooLarge { limit: 10 }));
+        assert_eq!(status, 413);
+        assert_eq!(body["error"], "The request body is larger than 10 bytes");
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "cut off");
+        assert_eq!(request("POST", "/parse", Err(BodyError::Io(io_error))).0, 400);
+    }
+
+    #[test]
+    fn rejects_other_methods_only_on_known_paths() {
+        assert_eq!(request("DELETE", "/search", Ok(String::new())).0, 405);
+        assert_eq!(request("POST", "/entry/1000000", Ok(String::new())).0, 405);
+        assert_eq!(request("PUT", "/kanji/犬", Ok(String::new())).0, 405);
+
+        assert_eq!(request("DELETE", "/search/x/y", Ok(String::new())).0, 404);
+        assert_eq!(request("POST", "/entry", Ok(String::new())).0, 404);
+        assert_eq!(get("/entry").0, 404);
+        assert_eq!(get("/nowhere").0, 404);
+    }
+}
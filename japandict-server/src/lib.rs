@@ -0,0 +1,95 @@
+//! Dictionary lookups as JSON over HTTP
+//!
+//! Used by the `japandict-server` binary and by `japandict serve`. The
+//! dictionary must be installed (if not embedded) before `serve` is called.
+
+pub mod api;
+
+use api::BodyError;
+use japandict_core::{build_search_indices, dictionary_info, languages};
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Request, Server};
+
+/// Longer `POST /parse` bodies are rejected with 413 Payload Too Large
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+pub struct ServeOptions {
+    pub host: String,
+    pub port: u16,
+    /// Default gloss language for responses; requests can override it with `lang=`
+    pub lang: String,
+    /// Request handler threads
+    pub threads: usize,
+}
+
+/// Build the search indices and answer requests until the process is stopped.
+/// Fails if the language is unknown or the address can't be bound.
+pub fn serve(options: ServeOptions) -> Result<(), String> {
+    if !languages().contains(&options.lang.as_str()) {
+        return Err(format!("the dictionary data has no '{}' glosses (it has: {})", options.lang, languages().join(", ")));
+    }
+
+    build_search_indices();
+
+    let address = format!("{}:{}", options.host, options.port);
+    let server = Arc::new(Server::http(&address).map_err(|err| format!("failed to listen on {}: {}", address, err))?);
+    log::info!("{}", dictionary_info());
+    log::info!("Listening on http://{}", address);
+
+    let handlers: Vec<_> = (0..options.threads.max(1))
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let lang = options.lang.clone();
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    respond(request, &lang);
+                }
+            })
+        })
+        .collect();
+    for handler in handlers {
+        let _ = handler.join();
+    }
+    Ok(())
+}
+
+/// One handler thread per core
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+fn respond(mut request: Request, lang: &str) {
+    let method = request.method().as_str().to_string();
+    let url = request.url().to_string();
+    let declared_length = request.body_length();
+    let reader = request.as_reader();
+    let response = api::handle(
+        &method,
+        &url,
+        || {
+            let too_large = BodyError::TooLarge { limit: MAX_BODY_BYTES };
+            if declared_length.is_some_and(|length| length as u64 > MAX_BODY_BYTES) {
+                return Err(too_large);
+            }
+            // Read one byte past the limit to tell a body that fills it from a longer one
+            let mut body = Vec::new();
+            reader.take(MAX_BODY_BYTES + 1).read_to_end(&mut body).map_err(BodyError::Io)?;
+            if body.len() as u64 > MAX_BODY_BYTES {
+                return Err(too_large);
+            }
+            String::from_utf8(body).map_err(|err| BodyError::Io(io::Error::new(io::ErrorKind::InvalidData, err)))
+        },
+        lang,
+    );
+
+    let status = response.status;
+    let response = tiny_http::Response::from_string(response.body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json; charset=utf-8").unwrap())
+        .with_header(Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap());
+    if let Err(err) = request.respond(response) {
+        log::warn!("Failed to send the response to {} {}: {}", method, url, err);
+    }
+}
@@ -0,0 +1,71 @@
+use clap::Parser;
+use flate2::read::GzDecoder;
+use japandict_core::{default_language, Dictionary};
+use japandict_server::{default_threads, serve, ServeOptions};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+#[command(name = "japandict-server")]
+#[command(about = "Serve dictionary lookups as JSON over HTTP")]
+struct Args {
+    /// Port to listen on
+    #[arg(short, long, default_value = "8080")]
+    port: u16,
+
+    /// Address to bind; use 0.0.0.0 to accept connections from other machines
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Default gloss language for responses, as a JMdict language code;
+    /// requests can override it with `lang=`
+    #[arg(long)]
+    lang: Option<String>,
+
+    /// Binary dictionary file from `jmdict-codegen --format binary` (optionally
+    /// .gz) to serve instead of the embedded data
+    #[arg(long)]
+    data: Option<PathBuf>,
+
+    /// Number of request handler threads (defaults to the number of cores)
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+fn main() {
+    let args = Args::parse();
+    // `serve` logs the data it loaded and the address it listens on
+    env_logger::Builder::new()
+        .filter_level(log::LevelFilter::Info)
+        .parse_default_env()
+        .format(|buf, record| writeln!(buf, "{}: {}", record.level().as_str().to_lowercase(), record.args()))
+        .init();
+
+    if let Some(path) = &args.data {
+        let dictionary = Dictionary::from_bytes(read_data(path).expect("Failed to read dictionary file"))
+            .expect("Failed to load dictionary file");
+        dictionary.install().expect("Failed to install dictionary");
+    }
+
+    let options = ServeOptions {
+        host: args.host,
+        port: args.port,
+        lang: args.lang.unwrap_or_else(|| default_language().to_string()),
+        threads: args.threads.unwrap_or_else(default_threads),
+    };
+    if let Err(err) = serve(options) {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn read_data(path: &Path) -> std::io::Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        return Ok(decompressed);
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,201 @@
+mod render;
+
+use clap::Parser;
+use flate2::read::GzDecoder;
+use japandict_core::{
+    cross_references, default_language, dictionary_info, examples_for, languages, try_get_word_entry,
+    Dictionary,
+};
+use render::{EntryPage, Reference};
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Sitemaps may list at most 50,000 URLs each
+const SITEMAP_URL_LIMIT: usize = 50_000;
+
+#[derive(Parser)]
+#[command(name = "japandict-site")]
+#[command(about = "Prerender a static HTML page for every dictionary entry")]
+struct Args {
+    /// Directory to write the site into
+    #[arg(short, long, default_value = "site")]
+    output: PathBuf,
+
+    /// Public URL the site is served from, for canonical links and the sitemap
+    #[arg(long)]
+    base_url: String,
+
+    /// Gloss language for the senses, as a JMdict language code; defaults to English
+    #[arg(long)]
+    lang: Option<String>,
+
+    /// Binary dictionary file from `jmdict-codegen --format binary` (optionally
+    /// .gz) to use instead of the embedded data
+    #[arg(long)]
+    data: Option<PathBuf>,
+
+    /// Maximum number of entries to render (0 for all)
+    #[arg(long, default_value = "0")]
+    limit: usize,
+}
+
+/// One entry in search-index.json
+#[derive(Serialize)]
+struct IndexRecord {
+    id: &'static str,
+    url: String,
+    kanji: Vec<&'static str>,
+    kana: Vec<&'static str>,
+    /// Glosses of the first sense
+    gloss: String,
+    common: bool,
+}
+
+fn main() {
+    let args = Args::parse();
+    let base_url = args.base_url.trim_end_matches('/');
+
+    if let Some(path) = &args.data {
+        let dictionary = Dictionary::from_bytes(read_data(path).expect("Failed to read dictionary file"))
+            .expect("Failed to load dictionary file");
+        dictionary.install().expect("Failed to install dictionary");
+    }
+
+    let lang = args.lang.as_deref().unwrap_or(default_language());
+    if !languages().contains(&lang) {
+        eprintln!("Error: the dictionary data has no '{}' glosses (it has: {})", lang, languages().join(", "));
+        std::process::exit(1);
+    }
+
+    let info = dictionary_info();
+    let data_info = info.to_string();
+    let count = match args.limit {
+        0 => info.word_count,
+        limit => limit.min(info.word_count),
+    };
+
+    let entry_dir = args.output.join("entry");
+    fs::create_dir_all(&entry_dir).expect("Failed to create output directory");
+
+    let mut records = Vec::with_capacity(count);
+    for index in 0..count {
+        let entry = match try_get_word_entry(index) {
+            Ok(entry) => entry,
+            Err(err) => {
+                eprintln!("Warning: skipping entry {}: {}", index, err);
+                continue;
+            }
+        };
+
+        // Entries past the limit or skipped as corrupt get no page to link to
+        let references: Vec<Reference> = cross_references(entry.id)
+            .into_iter()
+            .filter_map(|xref| {
+                let target = try_get_word_entry(xref.index).ok()?;
+                Some(Reference { kind: xref.kind, entry: target, has_page: xref.index < count })
+            })
+            .collect();
+        let html = render::entry_page(&EntryPage {
+            entry: &entry,
+            lang,
+            base_url,
+            references: &references,
+            examples: &examples_for(entry.id),
+            data_info: &data_info,
+        });
+        fs::write(args.output.join(render::entry_path(entry.id)), html).expect("Failed to write entry page");
+
+        records.push(IndexRecord {
+            id: entry.id,
+            url: render::entry_path(entry.id),
+            gloss: entry.senses_in(lang).first().map(|glosses| glosses.join("; ")).unwrap_or_default(),
+            kanji: entry.kanji,
+            kana: entry.kana,
+            common: entry.is_common,
+        });
+    }
+
+    let index = serde_json::to_string(&records).expect("Failed to serialize search index");
+    fs::write(args.output.join("search-index.json"), index).expect("Failed to write search index");
+    write_sitemaps(&args.output, base_url, &records).expect("Failed to write sitemap");
+    fs::write(args.output.join("index.html"), render::index_page(records.len(), &data_info)).expect("Failed to write index page");
+    fs::write(args.output.join("style.css"), render::STYLESHEET).expect("Failed to write stylesheet");
+
+    println!("Rendered {} entries into {}", records.len(), args.output.display());
+}
+
+fn read_data(path: &Path) -> std::io::Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        return Ok(decompressed);
+    }
+    Ok(bytes)
+}
+
+/// sitemap.xml is an index of sitemap-N.xml files, each within the URL limit
+fn write_sitemaps(output: &Path, base_url: &str, records: &[IndexRecord]) -> std::io::Result<()> {
+    let mut sitemap_index = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+
+    for (i, chunk) in records.chunks(SITEMAP_URL_LIMIT).enumerate() {
+        let name = format!("sitemap-{}.xml", i + 1);
+        let mut sitemap = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        if i == 0 {
+            let _ = writeln!(sitemap, "<url><loc>{}/index.html</loc></url>", render::escape(base_url));
+        }
+        for record in chunk {
+            let _ = writeln!(sitemap, "<url><loc>{}/{}</loc></url>", render::escape(base_url), record.url);
+        }
+        sitemap.push_str("</urlset>\n");
+        fs::write(output.join(&name), sitemap)?;
+
+        let _ = writeln!(sitemap_index, "<sitemap><loc>{}/{}</loc></sitemap>", render::escape(base_url), name);
+    }
+
+    sitemap_index.push_str("</sitemapindex>\n");
+    fs::write(output.join("sitemap.xml"), sitemap_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_the_sitemap_at_the_url_limit() {
+        let output = std::env::temp_dir().join(format!("japandict-site-sitemap-{}", std::process::id()));
+        fs::create_dir_all(&output).unwrap();
+        let records: Vec<IndexRecord> = (0..SITEMAP_URL_LIMIT + 1)
+            .map(|i| IndexRecord {
+                id: "",
+                url: render::entry_path(&i.to_string()),
+                kanji: Vec::new(),
+                kana: Vec::new(),
+                gloss: String::new(),
+                common: false,
+            })
+            .collect();
+
+        write_sitemaps(&output, "https://example.com", &records).unwrap();
+        let index = fs::read_to_string(output.join("sitemap.xml")).unwrap();
+        let first = fs::read_to_string(output.join("sitemap-1.xml")).unwrap();
+        let second = fs::read_to_string(output.join("sitemap-2.xml")).unwrap();
+        let third = output.join("sitemap-3.xml").exists();
+        fs::remove_dir_all(&output).unwrap();
+
+        assert_eq!(index.matches("<sitemap>").count(), 2);
+        assert!(index.contains("<loc>https://example.com/sitemap-2.xml</loc>"));
+        // The first file also lists the index page
+        assert_eq!(first.matches("<url>").count(), SITEMAP_URL_LIMIT + 1);
+        assert_eq!(second.matches("<url>").count(), 1);
+        assert!(second.contains(&format!("<loc>https://example.com/entry/{}.html</loc>", SITEMAP_URL_LIMIT)));
+        assert!(!third);
+    }
+}
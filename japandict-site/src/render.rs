@@ -0,0 +1,260 @@
+//! HTML for the generated pages
+//!
+//! Mirrors what the web app's `EntryDetail` shows (headword, pitch accents,
+//! parts of speech, numbered senses, examples), as plain markup with a shared
+//! stylesheet so the pages work without JavaScript.
+
+use japandict_core::{pitch, ExampleSentence, WordEntry, XrefKind};
+use std::fmt::Write;
+
+pub const STYLESHEET: &str = include_str!("style.css");
+
+/// Escape text for use in element content and quoted attributes
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The first written form, or the first reading for kana-only words
+pub fn headword(entry: &WordEntry) -> &'static str {
+    entry.kanji.first().or(entry.kana.first()).copied().unwrap_or_default()
+}
+
+/// Path of an entry's page, relative to the site root
+pub fn entry_path(id: &str) -> String {
+    format!("entry/{}.html", id)
+}
+
+/// A cross-referenced entry. Only entries the site has a page for (see
+/// `--limit`) are linked; the others are shown as plain text.
+pub struct Reference {
+    pub kind: XrefKind,
+    pub entry: WordEntry,
+    pub has_page: bool,
+}
+
+/// Everything on an entry page besides the entry itself
+pub struct EntryPage<'a> {
+    pub entry: &'a WordEntry,
+    pub lang: &'a str,
+    pub base_url: &'a str,
+    pub references: &'a [Reference],
+    pub examples: &'a [ExampleSentence],
+    /// One-line description of the data, from `dictionary_info()`
+    pub data_info: &'a str,
+}
+
+pub fn entry_page(page: &EntryPage) -> String {
+    let entry = page.entry;
+    let senses = entry.senses_in(page.lang);
+    let title = headword(entry);
+    let description = senses.first().map(|glosses| glosses.join("; ")).unwrap_or_default();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title} – JapanDict</title>\n\
+         <meta name=\"description\" content=\"{description}\">\n\
+         <link rel=\"canonical\" href=\"{base_url}/{path}\">\n\
+         <link rel=\"stylesheet\" href=\"../style.css\">\n</head>\n<body>\n\
+         <nav><a href=\"../index.html\">JapanDict</a></nav>\n<main>\n<article>\n",
+        title = escape(title),
+        description = escape(&description),
+        base_url = escape(page.base_url),
+        path = entry_path(entry.id),
+    );
+
+    // Headword, with the other written forms and readings underneath
+    let _ = write!(html, "<h1 lang=\"ja\">{}", escape(title));
+    if entry.is_common {
+        html.push_str(" <span class=\"common\">common</span>");
+    }
+    html.push_str("</h1>\n");
+    let other_forms: Vec<&str> = entry.kanji.iter().skip(1).copied().collect();
+    if !other_forms.is_empty() {
+        let _ = writeln!(html, "<p class=\"forms\" lang=\"ja\">Also written {}</p>", escape(&other_forms.join("、")));
+    }
+    if !entry.kanji.is_empty() || entry.kana.len() > 1 {
+        html.push_str("<ul class=\"readings\" lang=\"ja\">\n");
+        for (i, kana) in entry.kana.iter().enumerate() {
+            let accents = entry.accents.get(i).map(Vec::as_slice).unwrap_or_default();
+            let _ = writeln!(html, "<li>{}</li>", reading(kana, accents));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    if !entry.pos.is_empty() {
+        html.push_str("<p class=\"pos\">");
+        for pos in &entry.pos {
+            let _ = write!(html, "<span>{}</span>", escape(pos));
+        }
+        html.push_str("</p>\n");
+    }
+
+    // Every sense, numbered as in JMdict
+    if !senses.is_empty() {
+        html.push_str("<ol class=\"senses\">\n");
+        for glosses in &senses {
+            let _ = writeln!(html, "<li>{}</li>", escape(&glosses.join("; ")));
+        }
+        html.push_str("</ol>\n");
+    }
+
+    for (kind, label) in [(XrefKind::Related, "See also"), (XrefKind::Antonym, "Antonyms")] {
+        let links: Vec<&Reference> = page.references.iter().filter(|reference| reference.kind == kind).collect();
+        if links.is_empty() {
+            continue;
+        }
+        let _ = write!(html, "<p class=\"xrefs\">{}: ", label);
+        for (i, reference) in links.iter().enumerate() {
+            if i > 0 {
+                html.push_str(", ");
+            }
+            let target = &reference.entry;
+            if reference.has_page {
+                let _ = write!(html, "<a href=\"{}.html\" lang=\"ja\">{}</a>", escape(target.id), escape(headword(target)));
+            } else {
+                let _ = write!(html, "<span lang=\"ja\">{}</span>", escape(headword(target)));
+            }
+        }
+        html.push_str("</p>\n");
+    }
+
+    if !page.examples.is_empty() {
+        html.push_str("<h2>Examples</h2>\n<ul class=\"examples\">\n");
+        for example in page.examples {
+            let (before, highlight, after) = example.split_highlight();
+            let _ = writeln!(
+                html,
+                "<li><p lang=\"ja\">{}<mark>{}</mark>{}</p><p>{}</p></li>",
+                escape(before),
+                escape(highlight),
+                escape(after),
+                escape(example.english),
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let _ = write!(html, "</article>\n</main>\n{}</body>\n</html>\n", footer(&format!("JMdict {}", entry.id), page.data_info));
+    html
+}
+
+/// A reading with its pitch accents: high morae are overlined and the
+/// downstep is marked after the last high mora
+fn reading(kana: &str, accents: &[u8]) -> String {
+    if accents.is_empty() {
+        return escape(kana);
+    }
+
+    let morae = pitch::morae(kana);
+    let mut html = String::new();
+    for (i, &accent) in accents.iter().enumerate() {
+        if i > 0 {
+            html.push_str(" / ");
+        }
+        let pattern = pitch::pitch_pattern(morae.len(), accent);
+        html.push_str("<span class=\"pitch\">");
+        for (mora, pair) in morae.iter().zip(pattern.windows(2)) {
+            let class = match (pair[0], pair[1]) {
+                (true, false) => "high drop",
+                (true, true) => "high",
+                _ => "low",
+            };
+            let _ = write!(html, "<span class=\"{}\">{}</span>", class, escape(mora));
+        }
+        let _ = write!(html, "</span> <span class=\"accent\">[{}]</span>", accent);
+    }
+    html
+}
+
+pub fn index_page(word_count: usize, data_info: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>JapanDict</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n\
+         <main>\n<h1>JapanDict</h1>\n\
+         <p>{} Japanese word entries, one page each. The full list is in the \
+         <a href=\"sitemap.xml\">sitemap</a> and <a href=\"search-index.json\">search index</a>.</p>\n\
+         </main>\n{}</body>\n</html>\n",
+        word_count,
+        footer("", data_info),
+    )
+}
+
+fn footer(prefix: &str, data_info: &str) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if !prefix.is_empty() {
+        parts.push(escape(prefix));
+    }
+    parts.push(escape(data_info));
+    parts.push(
+        "Dictionary data from <a href=\"https://www.edrdg.org/jmdict/j_jmdict.html\">JMdict</a> \
+         (EDRDG, CC BY-SA 4.0); examples from <a href=\"https://tatoeba.org\">Tatoeba</a> (CC BY 2.0 FR)"
+            .to_string(),
+    );
+    format!("<footer>{}</footer>\n", parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use japandict_core::Gloss;
+
+    fn word(id: &'static str, kanji: &'static str, kana: &'static str, gloss: &'static str) -> WordEntry {
+        WordEntry {
+            id,
+            kanji: vec![kanji],
+            kana: vec![kana],
+            english: vec![gloss],
+            glosses: vec![Gloss { lang: "eng", text: gloss, sense: 0 }],
+            pos: vec!["n"],
+            is_common: true,
+            accents: vec![vec![0]],
+        }
+    }
+
+    fn page(entry: &WordEntry, references: &[Reference]) -> String {
+        entry_page(&EntryPage {
+            entry,
+            lang: "eng",
+            base_url: "https://example.com",
+            references,
+            examples: &[],
+            data_info: "JMdict 3.6.1",
+        })
+    }
+
+    #[test]
+    fn escapes_quotes_and_ampersands() {
+        assert_eq!(escape(r#"Tom & "Jerry's" <b>"#), "Tom &amp; &quot;Jerry&#39;s&quot; &lt;b&gt;");
+
+        let html = page(&word("1000000", "犬", "いぬ", "dog \"hound\" & pup"), &[]);
+        assert!(html.contains("<li>dog &quot;hound&quot; &amp; pup</li>"));
+        assert!(!html.contains("\"hound\""));
+    }
+
+    #[test]
+    fn links_only_references_with_a_page() {
+        let references = [
+            Reference { kind: XrefKind::Related, entry: word("1000001", "猫", "ねこ", "cat"), has_page: true },
+            Reference { kind: XrefKind::Related, entry: word("1000002", "狼", "おおかみ", "wolf"), has_page: false },
+        ];
+        let html = page(&word("1000000", "犬", "いぬ", "dog"), &references);
+        assert!(html.contains("<a href=\"1000001.html\" lang=\"ja\">猫</a>"));
+        assert!(html.contains("<span lang=\"ja\">狼</span>"));
+        assert!(!html.contains("1000002.html"));
+    }
+}
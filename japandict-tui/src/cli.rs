@@ -0,0 +1,201 @@
+//! Command-line interface definition
+//!
+//! Kept apart from `main` so shell completions and the man page are generated
+//! from the same definition (`japandict completions <shell>`, `japandict man`).
+
+use crate::output::OutputFormat;
+use clap::{Args, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "japandict", version)]
+#[command(about = "Japanese dictionary CLI using JMDict")]
+#[command(after_help = "Without a command, a query searches, piped stdin is looked up line by line, and otherwise the REPL starts.")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Shorthand for `search`
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    /// Old spelling of `info`, from before the subcommands
+    #[arg(long, hide = true)]
+    pub version_data: bool,
+
+    /// Old spelling of `info --validate`
+    #[arg(long, hide = true)]
+    pub validate_data: bool,
+}
+
+#[derive(Args)]
+pub struct GlobalArgs {
+    /// Gloss language for definitions and non-Japanese queries, as a JMdict
+    /// language code (eng, ger, fre, ...); defaults to English
+    #[arg(long, global = true)]
+    pub lang: Option<String>,
+
+    /// Only log errors to stderr
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Log progress (index build time, data counts) to stderr; repeat for debug output
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Search for words and print the results
+    Search(SearchArgs),
+    /// Look up one query per line from a file or stdin, building the indices once
+    Batch(BatchArgs),
+    /// Prompt for queries one after another
+    Repl(ReplArgs),
+    /// Full-screen interface with a results list, kanji panel and radical picker
+    Tui(TuiArgs),
+    /// Search as you type in the terminal
+    Live,
+    /// Print one entry in full by JMdict id
+    Entry(EntryArgs),
+    /// Print KANJIDIC2 information for kanji
+    Kanji(KanjiArgs),
+    /// Split Japanese text into dictionary words
+    Parse(TextArgs),
+    /// Add readings to Japanese text in Anki furigana style: 日本語[にほんご]
+    Annotate(AnnotateArgs),
+    /// Write every entry in the dictionary data
+    Export(ExportArgs),
+    /// Serve lookups as JSON over HTTP (see japandict-server)
+    Serve(ServeArgs),
+    /// Print which JMdict build the dictionary data was generated from
+    Info(InfoArgs),
+    /// Print a shell completion script
+    Completions(CompletionsArgs),
+    /// Print the man page (roff)
+    Man,
+}
+
+#[derive(Args, Default)]
+pub struct ResultArgs {
+    /// Maximum number of results per query [default: 10, or 1 in batch mode]
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+
+    /// Output format; anything but `pretty` prints only the results, for
+    /// scripts [default: pretty, or tsv in batch mode]
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+}
+
+#[derive(Args, Default)]
+pub struct SearchArgs {
+    /// Search term(s)
+    pub query: Vec<String>,
+
+    /// Also show matching proper names (JMnedict) as a separate group
+    #[arg(long)]
+    pub names: bool,
+
+    #[command(flatten)]
+    pub results: ResultArgs,
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// File with one query per line; `-` reads stdin
+    #[arg(default_value = "-")]
+    pub file: PathBuf,
+
+    #[command(flatten)]
+    pub results: ResultArgs,
+}
+
+#[derive(Args)]
+pub struct ReplArgs {
+    /// Also show matching proper names (JMnedict) as a separate group
+    #[arg(long)]
+    pub names: bool,
+
+    #[command(flatten)]
+    pub results: ResultArgs,
+}
+
+#[derive(Args)]
+pub struct TuiArgs {
+    /// Also show matching proper names (JMnedict) as a separate group
+    #[arg(long)]
+    pub names: bool,
+}
+
+#[derive(Args)]
+pub struct EntryArgs {
+    /// JMdict entry id, e.g. 1358280
+    pub id: String,
+
+    #[arg(long, value_enum, default_value = "pretty")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct KanjiArgs {
+    /// Kanji to look up; every kanji in the text is printed
+    #[arg(required = true)]
+    pub text: Vec<String>,
+
+    #[arg(long, value_enum, default_value = "pretty")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct TextArgs {
+    /// Text to process; read from stdin when omitted
+    pub text: Vec<String>,
+
+    #[arg(long, value_enum, default_value = "pretty")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct AnnotateArgs {
+    /// Text to annotate; read from stdin when omitted
+    pub text: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// `json` and `jsonl` carry every gloss language; `tsv` and `plain` the `--lang` glosses
+    #[arg(long, value_enum, default_value = "jsonl")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Port to listen on
+    #[arg(short, long, default_value = "8080")]
+    pub port: u16,
+
+    /// Address to bind; use 0.0.0.0 to accept connections from other machines
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// Number of request handler threads (defaults to the number of cores)
+    #[arg(long)]
+    pub threads: Option<usize>,
+}
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Also check the dictionary data for corruption; exits with 1 if it is
+    #[arg(long)]
+    pub validate: bool,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    pub shell: Shell,
+}
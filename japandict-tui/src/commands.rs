@@ -0,0 +1,220 @@
+//! Subcommands that print something and exit: `entry`, `kanji`, `parse`,
+//! `annotate`, `export` and `info`
+
+use crate::accented_readings;
+use crate::output::{self, OutputFormat};
+use japandict_core::{
+    components_of, cross_references, dictionary_info, examples_for, get_by_jmdict_id, get_word_entry, kanji_in,
+    parse_text, validate, EntryError, KanjiInfo, WordEntry, XrefKind,
+};
+use std::io::{self, stdout, Read, Write};
+
+/// Print one entry with its readings and pitch accents, every sense,
+/// cross-references and example sentences
+pub fn entry(id: &str, lang: &str, format: OutputFormat) -> io::Result<()> {
+    let entry = match get_by_jmdict_id(id) {
+        Ok(entry) => entry,
+        Err(err @ EntryError::UnknownId(_)) => return Err(io::Error::new(io::ErrorKind::NotFound, err.to_string())),
+        Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err.to_string())),
+    };
+
+    let mut out = io::BufWriter::new(stdout().lock());
+    match format {
+        OutputFormat::Pretty => print_entry(&mut out, &entry, lang)?,
+        // A single entry is one object rather than a one-element array
+        OutputFormat::Json => output::write_entries(&mut out, OutputFormat::Jsonl, lang, [entry])?,
+        _ => output::write_entries(&mut out, format, lang, [entry])?,
+    }
+    out.flush()
+}
+
+fn print_entry(out: &mut impl Write, entry: &WordEntry, lang: &str) -> io::Result<()> {
+    if entry.kanji.is_empty() {
+        writeln!(out, "{}", accented_readings(entry))?;
+    } else {
+        writeln!(out, "{} ({})", entry.kanji.join(", "), accented_readings(entry))?;
+    }
+    if !entry.pos.is_empty() {
+        writeln!(out, "[{}]{}", entry.pos.join(", "), if entry.is_common { " ⭐" } else { "" })?;
+    }
+    writeln!(out)?;
+
+    for (i, glosses) in entry.senses_in(lang).iter().enumerate() {
+        writeln!(out, "{:2}. {}", i + 1, glosses.join("; "))?;
+    }
+
+    let xrefs = cross_references(entry.id);
+    if !xrefs.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "See also")?;
+        for xref in xrefs {
+            let target = get_word_entry(xref.index);
+            let form = target.kanji.first().or(target.kana.first()).copied().unwrap_or_default();
+            let kind = match xref.kind {
+                XrefKind::Related => "",
+                XrefKind::Antonym => " (antonym)",
+            };
+            writeln!(out, "  {}{} - {}", form, kind, target.id)?;
+        }
+    }
+
+    let examples = examples_for(entry.id);
+    if !examples.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Examples")?;
+        for example in examples {
+            writeln!(out, "  {}", example.japanese)?;
+            writeln!(out, "  {}", example.english)?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "JMdict {}", entry.id)
+}
+
+/// Print every kanji in `text` that KANJIDIC2 knows about; fails if there are none
+pub fn kanji(text: &str, format: OutputFormat) -> io::Result<()> {
+    let kanji = kanji_in(text);
+    if kanji.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("No KANJIDIC2 entry for any of '{}'", text)));
+    }
+
+    let mut out = io::BufWriter::new(stdout().lock());
+    if format == OutputFormat::Pretty {
+        for (i, info) in kanji.iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            print_kanji(&mut out, info)?;
+        }
+    } else {
+        output::write_kanji(&mut out, format, &kanji)?;
+    }
+    out.flush()
+}
+
+fn print_kanji(out: &mut impl Write, info: &KanjiInfo) -> io::Result<()> {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let radical = match info.radical_char() {
+        Some(c) => format!("{} ({})", c, info.radical),
+        None => "-".to_string(),
+    };
+    let parts: String = components_of(info.literal).iter().collect();
+
+    writeln!(out, "{}", info.literal)?;
+    writeln!(out, "On:       {}", info.on_readings.join("、"))?;
+    writeln!(out, "Kun:      {}", info.kun_readings.join("、"))?;
+    writeln!(out, "Meanings: {}", info.meanings.join("; "))?;
+    writeln!(out, "Strokes:  {}", info.stroke_count)?;
+    writeln!(out, "Radical:  {}", radical)?;
+    writeln!(out, "Parts:    {}", parts)?;
+    writeln!(out, "Grade:    {}", optional(info.grade.map(|g| g.to_string())))?;
+    writeln!(out, "JLPT:     {}", optional(info.jlpt.map(|j| j.to_string())))?;
+    writeln!(out, "Freq:     {}", optional(info.frequency.map(|f| format!("#{}", f))))
+}
+
+/// The text given on the command line, or stdin when there is none
+pub fn text_or_stdin(text: &[String]) -> io::Result<String> {
+    if !text.is_empty() {
+        return Ok(text.join(" "));
+    }
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Split text into dictionary words by longest match
+pub fn parse(text: &str, lang: &str, format: OutputFormat) -> io::Result<()> {
+    let tokens = parse_text(text);
+    let mut out = io::BufWriter::new(stdout().lock());
+    if format != OutputFormat::Pretty {
+        output::write_tokens(&mut out, format, lang, &tokens)?;
+        return out.flush();
+    }
+
+    for token in tokens.iter().filter(|token| !token.text.trim().is_empty()) {
+        match token.entries.first() {
+            Some(&index) => {
+                let entry = get_word_entry(index);
+                let glosses = entry.glosses_in(lang);
+                writeln!(
+                    out,
+                    "{}  {} → {}",
+                    token.text,
+                    entry.kana.join(", "),
+                    glosses[..glosses.len().min(3)].join("; ")
+                )?;
+            }
+            None => writeln!(out, "{}  ?", token.text.trim())?,
+        }
+    }
+    out.flush()
+}
+
+/// Add the reading of the best match after every word with kanji, in the
+/// furigana syntax Anki understands: `日本語[にほんご]を 勉強[べんきょう]する`.
+/// Anki ends the reading's base at the previous space, so one goes before
+/// each annotated word.
+pub fn annotate(text: &str) -> io::Result<()> {
+    let mut annotated = String::with_capacity(text.len() * 2);
+    for token in parse_text(text) {
+        let reading = token
+            .entries
+            .first()
+            .filter(|_| token.text.chars().any(is_kanji))
+            .and_then(|&index| get_word_entry(index).kana.first().copied());
+        match reading {
+            Some(reading) => {
+                if !annotated.is_empty() && !annotated.ends_with(char::is_whitespace) {
+                    annotated.push(' ');
+                }
+                annotated.push_str(&format!("{}[{}]", token.text, reading));
+            }
+            None => annotated.push_str(token.text),
+        }
+    }
+
+    let mut out = stdout().lock();
+    if annotated.ends_with('\n') {
+        write!(out, "{}", annotated)?;
+    } else {
+        writeln!(out, "{}", annotated)?;
+    }
+    out.flush()
+}
+
+fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}' | '々')
+}
+
+/// Write every entry in dictionary order
+pub fn export(lang: &str, format: OutputFormat) -> io::Result<()> {
+    let mut out = io::BufWriter::new(stdout().lock());
+    output::write_entries(&mut out, format, lang, (0..dictionary_info().word_count).map(get_word_entry))?;
+    out.flush()
+}
+
+/// Print which JMdict build the data came from and, with `validate`, check it
+/// for corruption
+pub fn info(validate_data: bool) {
+    let info = dictionary_info();
+    let limit = info.entry_limit.map_or("none".to_string(), |limit| limit.to_string());
+
+    println!("JMdict version:  {}", info.jmdict_version);
+    println!("Dictionary date: {}", info.dict_date);
+    println!("Languages:       {}", info.languages.join(", "));
+    println!("Entry limit:     {}", limit);
+    println!("Filters:         {}", if info.filters.is_empty() { "none" } else { info.filters });
+    println!("Codegen version: {}", info.codegen_version);
+    println!("Entries:         {} words, {} kanji, {} names", info.word_count, info.kanji_count, info.name_count);
+
+    if validate_data {
+        match validate() {
+            Ok(()) => println!("Dictionary data OK ({} words)", info.word_count),
+            Err(err) => {
+                log::error!("Dictionary data is corrupted: {}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -1,6 +1,17 @@
-use clap::Parser;
-use dictionary_data::{WORD_COUNT, KANJI_STRINGS_COUNT, KANA_STRINGS_COUNT, ENGLISH_STRINGS_COUNT};
-use japandict_core::{search_dictionary, WordEntry};
+mod cli;
+mod commands;
+mod output;
+
+use clap::{CommandFactory, Parser};
+use cli::{BatchArgs, Cli, Command, InfoArgs, ReplArgs};
+use japandict_core::{
+    compatible_radicals, components_of, cross_references, default_language, dictionary_info, entry_details, entry_ref,
+    examples_for, get_word_entry, index_of_jmdict_id, kanji_by_components, kanji_in, languages, pitch, radicals,
+    search_dictionary_in, search_names, search_results_in, CrossReference, EntryDetails, ExampleSentence, FormDetails,
+    KanjiInfo, NameEntry, Radical, WordEntry, XrefKind,
+};
+use japandict_server::{default_threads, ServeOptions};
+use std::collections::BTreeSet;
 use rustyline::{Editor, Result};
 use crossterm::{
     cursor,
@@ -11,47 +22,58 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame, Terminal,
 };
-use std::io::{self, stdout, Write};
+use std::fs::File;
+use std::io::{self, stdout, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use output::{BatchWriter, OutputFormat};
 
-#[derive(Parser)]
-#[command(name = "dict_cli")]
-#[command(about = "Japanese dictionary CLI using JMDict")]
-struct Args {
-    /// Search term(s)
-    query: Vec<String>,
+fn format_name(entry: &NameEntry) -> String {
+    let mut output = String::new();
     
-    /// Maximum number of results to display
-    #[arg(short, long, default_value = "10")]
-    limit: usize,
+    if !entry.kanji.is_empty() {
+        output.push_str(&entry.kanji.join(", "));
+        if !entry.kana.is_empty() {
+            output.push_str(&format!(" ({})", entry.kana.join(", ")));
+        }
+    } else {
+        output.push_str(&entry.kana.join(", "));
+    }
     
-    /// Interactive mode (ignore query if provided)
-    #[arg(short, long)]
-    interactive: bool,
+    if !entry.translations.is_empty() {
+        output.push_str(&format!(" → {}", entry.translations[..entry.translations.len().min(3)].join("; ")));
+    }
     
-    /// Live search mode with real-time results as you type
-    #[arg(long)]
-    live: bool,
+    if !entry.name_types.is_empty() {
+        output.push_str(&format!(" [{}]", entry.name_types.join(", ")));
+    }
     
-    /// TUI mode with ratatui interface
-    #[arg(long)]
-    tui: bool,
+    output
 }
 
-fn search_and_display(query: &str, limit: usize) {
+fn search_and_display(query: &str, limit: usize, include_names: bool, lang: &str) {
     if query.trim().is_empty() {
         return;
     }
     
     let start = std::time::Instant::now();
-    let results = search_dictionary(query);
-    let duration = start.elapsed();
-    
+    let results = search_dictionary_in(query, lang);
+    display_results(query, &results, start.elapsed(), limit, include_names, lang);
+}
+
+fn display_results(
+    query: &str,
+    results: &[WordEntry],
+    duration: std::time::Duration,
+    limit: usize,
+    include_names: bool,
+    lang: &str,
+) {
     println!("🔍 Search Results for \"{}\"", query);
     println!("Found {} results in {:?}", results.len(), duration);
     println!("{}", "─".repeat(60));
@@ -73,8 +95,9 @@ fn search_and_display(query: &str, limit: usize) {
             print!("{}", entry.kana.join(", "));
         }
         
-        if !entry.english.is_empty() {
-            print!(" → {}", entry.english[..entry.english.len().min(3)].join("; "));
+        let glosses = entry.glosses_in(lang);
+        if !glosses.is_empty() {
+            print!(" → {}", glosses[..glosses.len().min(3)].join("; "));
         }
         
         if !entry.pos.is_empty() {
@@ -87,26 +110,280 @@ fn search_and_display(query: &str, limit: usize) {
         
         println!();
     }
+    
+    if include_names {
+        let names = search_names(query);
+        if !names.is_empty() {
+            println!("{}", "─".repeat(60));
+            println!("Names");
+            for (i, entry) in names.iter().take(limit).enumerate() {
+                println!("{:2}. {}", i + 1, format_name(entry));
+            }
+        }
+    }
     println!();
 }
 
+/// Search and print the results in `format`; scripts get nothing but results
+fn search_and_print(query: &str, limit: usize, include_names: bool, lang: &str, format: OutputFormat) {
+    if format == OutputFormat::Pretty {
+        search_and_display(query, limit, include_names, lang);
+        return;
+    }
+    
+    let mut results = search_results_in(query, lang);
+    results.truncate(limit);
+    let names = include_names.then(|| {
+        let mut names = search_names(query);
+        names.truncate(limit);
+        names
+    });
+    
+    let mut out = io::BufWriter::new(stdout().lock());
+    exit_on_error(output::write_results(&mut out, format, query, lang, &results, names.as_deref()).and_then(|()| out.flush()));
+}
+
+/// Look up every line of `input` (`-` for stdin) with the top `limit` results
+/// each. A blank line gets an empty result, so the output lines up with the
+/// input (e.g. for `paste`).
+fn run_batch(input: &Path, limit: usize, lang: &str, format: OutputFormat) -> io::Result<()> {
+    let reader: Box<dyn BufRead> = if input == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file = File::open(input).map_err(|err| io::Error::new(err.kind(), format!("{}: {}", input.display(), err)))?;
+        Box::new(BufReader::new(file))
+    };
+    
+    let mut writer = (format != OutputFormat::Pretty)
+        .then(|| BatchWriter::new(io::BufWriter::new(stdout().lock()), format, lang));
+    for line in reader.lines() {
+        let line = line?;
+        let query = line.trim();
+        match writer.as_mut() {
+            Some(writer) => {
+                let mut results = if query.is_empty() { Vec::new() } else { search_results_in(query, lang) };
+                results.truncate(limit);
+                writer.write(query, &results)?;
+            }
+            None => {
+                let start = std::time::Instant::now();
+                let results = if query.is_empty() { Vec::new() } else { search_dictionary_in(query, lang) };
+                display_results(query, &results, start.elapsed(), limit, false, lang);
+            }
+        }
+    }
+    writer.map_or(Ok(()), BatchWriter::finish)
+}
+
+/// Exit on an I/O error while printing results. A closed pipe (e.g. `| head`)
+/// just means the reader has seen enough.
+fn exit_on_error(result: io::Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => std::process::exit(0),
+        Err(err) => {
+            log::error!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+const PICKER_COLUMNS: usize = 20;
+
+enum PickerAction {
+    None,
+    Close,
+    Pick(char),
+}
+
+/// Radical picker: select components to narrow down kanji you can't type
+struct RadicalPicker {
+    radicals: Vec<Radical>,
+    selected: Vec<char>,
+    compatible: BTreeSet<char>,
+    candidates: Vec<char>,
+    cursor: usize,
+    candidate_cursor: usize,
+    in_candidates: bool,
+}
+
+impl RadicalPicker {
+    fn new() -> RadicalPicker {
+        RadicalPicker {
+            radicals: radicals(),
+            selected: Vec::new(),
+            compatible: compatible_radicals(&[]),
+            candidates: Vec::new(),
+            cursor: 0,
+            candidate_cursor: 0,
+            in_candidates: false,
+        }
+    }
+
+    fn update_candidates(&mut self) {
+        self.candidates = kanji_by_components(&self.selected);
+        self.compatible = compatible_radicals(&self.selected);
+        self.candidate_cursor = 0;
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(radical) = self.radicals.get(self.cursor) else {
+            return;
+        };
+        if let Some(i) = self.selected.iter().position(|&r| r == radical.literal) {
+            self.selected.remove(i);
+        } else if self.compatible.contains(&radical.literal) {
+            self.selected.push(radical.literal);
+        }
+        self.update_candidates();
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> PickerAction {
+        let (len, cursor, row) = if self.in_candidates {
+            (self.candidates.len(), &mut self.candidate_cursor, 1)
+        } else {
+            (self.radicals.len(), &mut self.cursor, PICKER_COLUMNS)
+        };
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => return PickerAction::Close,
+            (KeyCode::Tab, _) => self.in_candidates = !self.in_candidates && !self.candidates.is_empty(),
+
+            (KeyCode::Right, _) | (KeyCode::Char('f'), KeyModifiers::CONTROL) => {
+                *cursor = (*cursor + 1).min(len.saturating_sub(1));
+            }
+            (KeyCode::Left, _) | (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
+                *cursor = cursor.saturating_sub(1);
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                *cursor = (*cursor + row).min(len.saturating_sub(1));
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                *cursor = cursor.saturating_sub(row);
+            }
+
+            (KeyCode::Char(' '), _) if !self.in_candidates => self.toggle_selected(),
+            (KeyCode::Backspace, _) => {
+                self.selected.pop();
+                self.in_candidates = false;
+                self.update_candidates();
+            }
+            (KeyCode::Enter, _) => {
+                if self.in_candidates {
+                    if let Some(&kanji) = self.candidates.get(self.candidate_cursor) {
+                        return PickerAction::Pick(kanji);
+                    }
+                } else {
+                    self.toggle_selected();
+                }
+            }
+            _ => {}
+        }
+
+        PickerAction::None
+    }
+}
+
+enum DetailAction {
+    None,
+    Close,
+}
+
+/// Full entry view opened from a result. Following a cross-reference pushes
+/// the linked entry; going back pops it, and closes the view at the bottom.
+struct DetailView {
+    /// Pages opened so far; the last one is shown
+    stack: Vec<DetailPage>,
+}
+
+struct DetailPage {
+    index: usize,
+    xrefs: Vec<CrossReference>,
+    /// Selected cross-reference
+    link: usize,
+    scroll: u16,
+}
+
+impl DetailPage {
+    fn new(index: usize) -> DetailPage {
+        DetailPage { index, xrefs: cross_references(entry_ref(index).id()), link: 0, scroll: 0 }
+    }
+}
+
+impl DetailView {
+    fn new(index: usize) -> DetailView {
+        DetailView { stack: vec![DetailPage::new(index)] }
+    }
+
+    fn page(&self) -> &DetailPage {
+        self.stack.last().expect("detail view has a page")
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> DetailAction {
+        let Some(page) = self.stack.last_mut() else {
+            return DetailAction::Close;
+        };
+
+        match (key.code, key.modifiers) {
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => return DetailAction::Close,
+            (KeyCode::Esc, _) | (KeyCode::Backspace, _) | (KeyCode::Left, _) | (KeyCode::Char('b'), KeyModifiers::CONTROL) => {
+                self.stack.pop();
+                if self.stack.is_empty() {
+                    return DetailAction::Close;
+                }
+            }
+
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => page.scroll = page.scroll.saturating_add(1),
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => page.scroll = page.scroll.saturating_sub(1),
+            (KeyCode::PageDown, _) => page.scroll = page.scroll.saturating_add(10),
+            (KeyCode::PageUp, _) => page.scroll = page.scroll.saturating_sub(10),
+
+            (KeyCode::Tab, _) if !page.xrefs.is_empty() => page.link = (page.link + 1) % page.xrefs.len(),
+            (KeyCode::BackTab, _) if !page.xrefs.is_empty() => {
+                page.link = (page.link + page.xrefs.len() - 1) % page.xrefs.len();
+            }
+            (KeyCode::Enter, _) | (KeyCode::Right, _) => {
+                if let Some(xref) = page.xrefs.get(page.link) {
+                    let index = xref.index;
+                    self.stack.push(DetailPage::new(index));
+                }
+            }
+            _ => {}
+        }
+
+        DetailAction::None
+    }
+}
+
 struct App {
     query: String,
     cursor_pos: usize,
     results: Vec<WordEntry>,
+    names: Vec<NameEntry>,
+    show_names: bool,
+    lang: String,
     search_time: Option<std::time::Duration>,
     scroll: usize,
+    kanji_focus: usize,
+    picker: Option<RadicalPicker>,
+    detail: Option<DetailView>,
     should_quit: bool,
 }
 
 impl App {
-    fn new() -> App {
+    fn new(show_names: bool, lang: String) -> App {
         App {
             query: String::new(),
             cursor_pos: 0,
             results: Vec::new(),
+            names: Vec::new(),
+            show_names,
+            lang,
             search_time: None,
             scroll: 0,
+            kanji_focus: 0,
+            picker: None,
+            detail: None,
             should_quit: false,
         }
     }
@@ -114,19 +391,54 @@ impl App {
     fn search(&mut self) {
         if self.query.trim().is_empty() {
             self.results.clear();
+            self.names.clear();
             self.search_time = None;
             return;
         }
 
         let start = std::time::Instant::now();
-        self.results = search_dictionary(&self.query);
+        self.results = search_dictionary_in(&self.query, &self.lang);
+        self.names = if self.show_names { search_names(&self.query) } else { Vec::new() };
         self.search_time = Some(start.elapsed());
         self.scroll = 0;
+        self.kanji_focus = 0;
+    }
+
+    /// Number of list rows: word results, then a header and the name results
+    fn item_count(&self) -> usize {
+        if self.names.is_empty() {
+            self.results.len()
+        } else {
+            self.results.len() + 1 + self.names.len()
+        }
     }
 
     fn handle_input(&mut self, key: KeyEvent) {
         use crossterm::event::KeyModifiers;
         
+        if let Some(picker) = &mut self.picker {
+            match picker.handle_input(key) {
+                PickerAction::None => {}
+                PickerAction::Close => self.picker = None,
+                PickerAction::Pick(kanji) => {
+                    // Feed the picked kanji straight into the search
+                    self.query.insert(self.cursor_pos, kanji);
+                    self.cursor_pos += kanji.len_utf8();
+                    self.picker = None;
+                    self.search();
+                }
+            }
+            return;
+        }
+        
+        if let Some(detail) = &mut self.detail {
+            match detail.handle_input(key) {
+                DetailAction::None => {}
+                DetailAction::Close => self.detail = None,
+            }
+            return;
+        }
+        
         match (key.code, key.modifiers) {
             // Quit commands
             (KeyCode::Char('q'), KeyModifiers::NONE) => self.should_quit = true,
@@ -140,29 +452,55 @@ impl App {
             (KeyCode::Char('e'), KeyModifiers::CONTROL) => {
                 self.cursor_pos = self.query.len();
             }
-            (KeyCode::Char('f'), KeyModifiers::CONTROL) | (KeyCode::Right, _) => {
-                if self.cursor_pos < self.query.len() {
-                    self.cursor_pos += 1;
-                }
+            (KeyCode::Char('f'), KeyModifiers::CONTROL) | (KeyCode::Right, _) if self.cursor_pos < self.query.len() => {
+                self.cursor_pos += 1;
             }
-            (KeyCode::Char('b'), KeyModifiers::CONTROL) | (KeyCode::Left, _) => {
-                if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
-                }
+            (KeyCode::Char('b'), KeyModifiers::CONTROL) | (KeyCode::Left, _) if self.cursor_pos > 0 => {
+                self.cursor_pos -= 1;
             }
             
             // Readline-style result navigation
-            (KeyCode::Char('n'), KeyModifiers::CONTROL) | (KeyCode::Down, _) => {
-                if self.scroll < self.results.len().saturating_sub(1) {
-                    self.scroll += 1;
-                }
+            (KeyCode::Char('n'), KeyModifiers::CONTROL) | (KeyCode::Down, _) if self.scroll < self.item_count().saturating_sub(1) => {
+                self.scroll += 1;
             }
-            (KeyCode::Char('p'), KeyModifiers::CONTROL) | (KeyCode::Up, _) => {
-                if self.scroll > 0 {
-                    self.scroll -= 1;
+            (KeyCode::Char('p'), KeyModifiers::CONTROL) | (KeyCode::Up, _) if self.scroll > 0 => {
+                self.scroll -= 1;
+            }
+            
+            // Show/hide proper names
+            (KeyCode::Char('t'), KeyModifiers::CONTROL) => {
+                self.show_names = !self.show_names;
+                self.search();
+            }
+            
+            // Cycle through the gloss languages in the data
+            (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
+                let available = languages();
+                if !available.is_empty() {
+                    let next = available.iter().position(|&lang| lang == self.lang).map_or(0, |i| (i + 1) % available.len());
+                    self.lang = available[next].to_string();
+                    self.search();
                 }
             }
             
+            // Radical picker
+            (KeyCode::Char('r'), KeyModifiers::CONTROL) => {
+                self.picker = Some(RadicalPicker::new());
+            }
+            
+            // Open the selected word in the detail view
+            (KeyCode::Enter, _) => {
+                self.detail = self.results
+                    .get(self.scroll)
+                    .and_then(|entry| index_of_jmdict_id(entry.id).ok())
+                    .map(DetailView::new);
+            }
+            
+            // Cycle through the kanji of the selected result
+            (KeyCode::Tab, _) => {
+                self.kanji_focus = self.kanji_focus.wrapping_add(1);
+            }
+            
             // Readline-style editing
             (KeyCode::Char('k'), KeyModifiers::CONTROL) => {
                 self.query.truncate(self.cursor_pos);
@@ -173,18 +511,14 @@ impl App {
                 self.cursor_pos = 0;
                 self.search();
             }
-            (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
-                if self.cursor_pos < self.query.len() {
-                    self.query.remove(self.cursor_pos);
-                    self.search();
-                }
+            (KeyCode::Char('d'), KeyModifiers::CONTROL) if self.cursor_pos < self.query.len() => {
+                self.query.remove(self.cursor_pos);
+                self.search();
             }
-            (KeyCode::Backspace, _) | (KeyCode::Char('h'), KeyModifiers::CONTROL) => {
-                if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
-                    self.query.remove(self.cursor_pos);
-                    self.search();
-                }
+            (KeyCode::Backspace, _) | (KeyCode::Char('h'), KeyModifiers::CONTROL) if self.cursor_pos > 0 => {
+                self.cursor_pos -= 1;
+                self.query.remove(self.cursor_pos);
+                self.search();
             }
             
             // Regular character input
@@ -196,7 +530,7 @@ impl App {
             
             // Page navigation
             (KeyCode::PageDown, _) => {
-                self.scroll = (self.scroll + 10).min(self.results.len().saturating_sub(1));
+                self.scroll = (self.scroll + 10).min(self.item_count().saturating_sub(1));
             }
             (KeyCode::PageUp, _) => {
                 self.scroll = self.scroll.saturating_sub(10);
@@ -207,14 +541,52 @@ impl App {
     }
 }
 
-fn run_tui() -> Result<()> {
+/// Draw a reading in overline notation: high morae are overlined and a
+/// downstep mark follows the accent nucleus.
+fn accent_notation(kana: &str, accent: u8) -> String {
+    let morae = pitch::morae(kana);
+    let pattern = pitch::pitch_pattern(morae.len(), accent);
+    let mut output = String::new();
+    
+    for (i, mora) in morae.iter().enumerate() {
+        for c in mora.chars() {
+            output.push(c);
+            if pattern[i] {
+                output.push('\u{0305}');
+            }
+        }
+        if i + 1 == accent as usize {
+            output.push('ꜜ');
+        }
+    }
+    
+    output
+}
+
+fn accented_readings(entry: &WordEntry) -> String {
+    entry.kana
+        .iter()
+        .enumerate()
+        .map(|(i, kana)| match entry.accents.get(i) {
+            Some(accents) if !accents.is_empty() => accents
+                .iter()
+                .map(|&accent| accent_notation(kana, accent))
+                .collect::<Vec<_>>()
+                .join("/"),
+            _ => kana.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn run_tui(show_names: bool, lang: String) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(show_names, lang);
 
     loop {
         terminal.draw(|f| ui(f, &app))?;
@@ -240,9 +612,88 @@ fn ui(f: &mut Frame, app: &App) {
         .constraints([Constraint::Min(0), Constraint::Length(3)])
         .split(f.size());
 
+    if let Some(picker) = &app.picker {
+        render_radical_picker(f, picker, chunks[0]);
+    } else if let Some(detail) = &app.detail {
+        render_detail(f, detail, &app.lang, chunks[0]);
+    } else {
+        render_results(f, app, chunks[0]);
+    }
+
+    // Search input at bottom with cursor
+    let search_text = if app.query.is_empty() {
+        "Search: █".to_string()
+    } else {
+        let (before_cursor, after_cursor) = app.query.split_at(app.cursor_pos);
+        if app.cursor_pos >= app.query.len() {
+            format!("Search: {}█", app.query)
+        } else {
+            format!("Search: {}█{}", before_cursor, after_cursor)
+        }
+    };
+    
+    let help_text = if app.picker.is_some() {
+        "arrows:move Space:select Bksp:undo Tab:kanji/radicals Enter:pick Esc:close"
+    } else if app.detail.is_some() {
+        "Up/Down:scroll Tab/S-Tab:select link Enter:open link Esc/Bksp:back C-c:close"
+    } else {
+        "C-a:start C-e:end C-k:kill C-u:clear C-n/p:nav Enter:details Tab:kanji C-r:radicals C-t:names C-l:lang q/C-c:quit"
+    };
+    
+    let search_input = Paragraph::new(vec![
+        Line::from(search_text),
+        Line::from(Span::styled(help_text, Style::default().fg(Color::DarkGray))),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    )
+    .style(Style::default().fg(Color::White));
+    f.render_widget(search_input, chunks[1]);
+}
+
+fn render_results(f: &mut Frame, app: &App, area: Rect) {
+    // Kanji panel for the selected result, beside the results
+    let selected_kanji = app.results
+        .get(app.scroll)
+        .and_then(|entry| entry.kanji.first())
+        .map(|form| kanji_in(form))
+        .unwrap_or_default();
+    let results_area = if selected_kanji.is_empty() {
+        area
+    } else {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(area);
+        let focus = app.kanji_focus % selected_kanji.len();
+        f.render_widget(kanji_panel(&selected_kanji[focus], focus, selected_kanji.len()), columns[1]);
+        columns[0]
+    };
+
+    // Example sentences for the selected result, under the results
+    let examples: Vec<ExampleSentence> = app.results
+        .get(app.scroll)
+        .map(|entry| examples_for(entry.id))
+        .unwrap_or_default()
+        .into_iter()
+        .take(EXAMPLES_SHOWN)
+        .collect();
+    let results_area = if examples.is_empty() {
+        results_area
+    } else {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(examples.len() as u16 * 2 + 2)])
+            .split(results_area);
+        f.render_widget(examples_panel(&examples), rows[1]);
+        rows[0]
+    };
+
     // Results area
-    if !app.results.is_empty() {
-        let items: Vec<ListItem> = app.results
+    if app.item_count() > 0 {
+        let mut items: Vec<ListItem> = app.results
             .iter()
             .enumerate()
             .map(|(i, entry)| {
@@ -260,22 +711,23 @@ fn ui(f: &mut Frame, app: &App) {
                     // Kana in cyan
                     if !entry.kana.is_empty() {
                         spans.push(Span::styled(
-                            format!(" ({})", entry.kana.join(", ")),
+                            format!(" ({})", accented_readings(entry)),
                             Style::default().fg(Color::Cyan),
                         ));
                     }
                 } else if !entry.kana.is_empty() {
                     spans.push(Span::styled(
-                        entry.kana.join(", "),
+                        accented_readings(entry),
                         Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
                     ));
                 }
 
-                // English in green
-                if !entry.english.is_empty() {
+                // Glosses in green
+                let glosses = entry.glosses_in(&app.lang);
+                if !glosses.is_empty() {
                     spans.push(Span::styled(" → ", Style::default().fg(Color::DarkGray)));
                     spans.push(Span::styled(
-                        entry.english[..entry.english.len().min(3)].join("; "),
+                        glosses[..glosses.len().min(3)].join("; "),
                         Style::default().fg(Color::Green),
                     ));
                 }
@@ -300,16 +752,38 @@ fn ui(f: &mut Frame, app: &App) {
             })
             .collect();
 
+        // Proper names as a separate, lower-ranked group
+        if !app.names.is_empty() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("── Names ({}) ──", app.names.len()),
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+            ))));
+            items.extend(app.names.iter().map(|entry| {
+                ListItem::new(Line::from(Span::styled(
+                    format!("    {}", format_name(entry)),
+                    Style::default().fg(Color::Blue),
+                )))
+            }));
+        }
+
+        let mut title = if app.names.is_empty() {
+            format!("Results: {} found", app.results.len())
+        } else {
+            format!("Results: {} found, {} names", app.results.len(), app.names.len())
+        };
+        if languages().len() > 1 {
+            title.push_str(&format!(" [{}]", app.lang));
+        }
         let results_list = List::new(items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("Results: {} found", app.results.len()))
+                    .title(title)
                     .border_style(Style::default().fg(Color::White)),
             )
             .highlight_style(Style::default().bg(Color::DarkGray));
 
-        f.render_stateful_widget(results_list, chunks[0], &mut ratatui::widgets::ListState::default().with_selected(Some(app.scroll)));
+        f.render_stateful_widget(results_list, results_area, &mut ratatui::widgets::ListState::default().with_selected(Some(app.scroll)));
     } else {
         let no_results = Paragraph::new("Type to search Japanese dictionary...")
             .block(
@@ -320,37 +794,269 @@ fn ui(f: &mut Frame, app: &App) {
             )
             .alignment(Alignment::Center)
             .style(Style::default().fg(Color::DarkGray));
-        f.render_widget(no_results, chunks[0]);
+        f.render_widget(no_results, results_area);
     }
+}
 
-    // Search input at bottom with cursor
-    let search_text = if app.query.is_empty() {
-        "Search: █".to_string()
-    } else {
-        let (before_cursor, after_cursor) = app.query.split_at(app.cursor_pos);
-        if app.cursor_pos >= app.query.len() {
-            format!("Search: {}█", app.query)
-        } else {
-            format!("Search: {}█{}", before_cursor, after_cursor)
+/// Every form with its markers, readings with pitch accents, numbered senses
+/// with their own parts of speech and notes, cross-references and examples
+fn render_detail(f: &mut Frame, detail: &DetailView, lang: &str, area: Rect) {
+    let page = detail.page();
+    let entry = get_word_entry(page.index);
+    let dim = Style::default().fg(Color::DarkGray);
+    let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+    
+    let mut lines = Vec::new();
+    let details = entry_details(page.index).unwrap_or_else(|err| {
+        lines.push(Line::from(Span::styled(format!("Details unavailable: {}", err), Style::default().fg(Color::Red))));
+        EntryDetails::default()
+    });
+    for form in &details.kanji {
+        let mut spans = vec![Span::styled(form.text, Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))];
+        spans.extend(form_markers(form));
+        lines.push(Line::from(spans));
+    }
+    for (i, form) in details.kana.iter().enumerate() {
+        let reading = match entry.accents.get(i) {
+            Some(accents) if !accents.is_empty() => {
+                accents.iter().map(|&accent| accent_notation(form.text, accent)).collect::<Vec<_>>().join("/")
+            }
+            _ => form.text.to_string(),
+        };
+        let mut spans = vec![Span::styled(reading, Style::default().fg(Color::Cyan))];
+        spans.extend(form_markers(form));
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    
+    for (number, (sense, glosses)) in entry.senses_with_index_in(lang).iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:2}. ", number + 1), dim),
+            Span::styled(glosses.join("; "), Style::default().fg(Color::Green)),
+        ]));
+        let Some(notes) = details.senses.get(*sense as usize) else {
+            continue;
+        };
+        let mut tags = Vec::new();
+        if !notes.pos.is_empty() {
+            tags.push(format!("[{}]", notes.pos.join(", ")));
+        }
+        tags.extend(notes.misc.iter().chain(&notes.field).chain(&notes.dialect).map(|tag| tag.to_string()));
+        if !tags.is_empty() {
+            lines.push(Line::from(Span::styled(format!("    {}", tags.join(" ")), dim.add_modifier(Modifier::ITALIC))));
+        }
+        for info in &notes.info {
+            lines.push(Line::from(Span::styled(format!("    {}", info), dim)));
         }
+    }
+    
+    if !page.xrefs.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("See also"));
+        for (i, xref) in page.xrefs.iter().enumerate() {
+            let target = get_word_entry(xref.index);
+            let kind = match xref.kind {
+                XrefKind::Related => "",
+                XrefKind::Antonym => " (antonym)",
+            };
+            let text = match (target.kanji.first(), target.kana.first()) {
+                (Some(kanji), Some(kana)) => format!("→ {} ({}){}", kanji, kana, kind),
+                (Some(form), None) | (None, Some(form)) => format!("→ {}{}", form, kind),
+                (None, None) => format!("→ {}{}", target.id, kind),
+            };
+            let style = if i == page.link {
+                Style::default().fg(Color::Blue).add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::Blue)
+            };
+            lines.push(Line::from(vec![Span::raw("  "), Span::styled(text, style)]));
+        }
+    }
+    
+    let examples = examples_for(entry.id);
+    if !examples.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(heading("Examples"));
+        for example in &examples {
+            let (before, headword, after) = example.split_highlight();
+            lines.push(Line::from(vec![
+                Span::raw(format!("  {}", before)),
+                Span::styled(headword, Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+                Span::raw(after),
+            ]));
+            lines.push(Line::from(Span::styled(format!("    {}", example.english), Style::default().fg(Color::Green))));
+        }
+    }
+    
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(format!("JMdict {}", entry.id), dim)));
+    
+    // Headwords of the pages on the back stack
+    let trail: Vec<&str> = detail.stack
+        .iter()
+        .map(|page| {
+            let entry = entry_ref(page.index);
+            entry.kanji().next().or(entry.kana().next()).unwrap_or_default()
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(trail.join(" › "))
+                .border_style(Style::default().fg(Color::White)),
+        )
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .scroll((page.scroll, 0));
+    f.render_widget(paragraph, area);
+}
+
+/// ⭐ for common forms, then JMdict tags and priority markers
+fn form_markers(form: &FormDetails) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    if form.common {
+        spans.push(Span::styled(" ⭐", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    if !form.tags.is_empty() {
+        spans.push(Span::styled(format!(" [{}]", form.tags.join(", ")), Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)));
+    }
+    if !form.priority.is_empty() {
+        spans.push(Span::styled(format!(" ({})", form.priority.join(", ")), Style::default().fg(Color::DarkGray)));
+    }
+    spans
+}
+
+fn render_radical_picker(f: &mut Frame, picker: &RadicalPicker, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(5)])
+        .split(area);
+
+    // Radical grid: selected radicals in magenta, ones that can't narrow the
+    // current selection any further dimmed
+    let grid: Vec<Line> = picker.radicals
+        .chunks(PICKER_COLUMNS)
+        .enumerate()
+        .map(|(row, radicals)| {
+            let spans: Vec<Span> = radicals
+                .iter()
+                .enumerate()
+                .map(|(col, radical)| {
+                    let mut style = if picker.selected.contains(&radical.literal) {
+                        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                    } else if picker.compatible.contains(&radical.literal) {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    if !picker.in_candidates && row * PICKER_COLUMNS + col == picker.cursor {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Span::styled(format!("{} ", radical.literal), style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    let stroke_count = picker.radicals.get(picker.cursor).map_or(0, |r| r.stroke_count);
+    let selected: String = picker.selected.iter().collect();
+    let radical_grid = Paragraph::new(grid)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Radicals ({} strokes) selected: {}", stroke_count, selected))
+                .border_style(Style::default().fg(Color::White)),
+        );
+    f.render_widget(radical_grid, rows[0]);
+
+    let candidates: Vec<Span> = picker.candidates
+        .iter()
+        .enumerate()
+        .map(|(i, kanji)| {
+            let mut style = Style::default().fg(Color::Magenta);
+            if picker.in_candidates && i == picker.candidate_cursor {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Span::styled(format!("{} ", kanji), style)
+        })
+        .collect();
+    let candidate_list = Paragraph::new(Line::from(candidates))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Kanji: {} matches", picker.candidates.len()))
+                .border_style(Style::default().fg(Color::White)),
+        )
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(candidate_list, rows[1]);
+}
+
+fn kanji_panel(info: &KanjiInfo, position: usize, total: usize) -> Paragraph<'static> {
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::DarkGray));
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    
+    let radical = match info.radical_char() {
+        Some(c) => format!("{} ({})", c, info.radical),
+        None => "-".to_string(),
     };
+    let parts: String = components_of(info.literal).iter().collect();
     
-    let help_text = "C-a:start C-e:end C-k:kill C-u:clear C-n/p:nav q/C-c:quit";
+    let lines = vec![
+        Line::from(Span::styled(
+            info.literal.to_string(),
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![label("On:       "), Span::styled(info.on_readings.join("、"), Style::default().fg(Color::Cyan))]),
+        Line::from(vec![label("Kun:      "), Span::styled(info.kun_readings.join("、"), Style::default().fg(Color::Cyan))]),
+        Line::from(vec![label("Meanings: "), Span::styled(info.meanings.join("; "), Style::default().fg(Color::Green))]),
+        Line::from(""),
+        Line::from(vec![label("Strokes:  "), Span::raw(info.stroke_count.to_string())]),
+        Line::from(vec![label("Radical:  "), Span::raw(radical)]),
+        Line::from(vec![label("Parts:    "), Span::raw(parts)]),
+        Line::from(vec![label("Grade:    "), Span::raw(optional(info.grade.map(|g| g.to_string())))]),
+        Line::from(vec![label("JLPT:     "), Span::raw(optional(info.jlpt.map(|j| j.to_string())))]),
+        Line::from(vec![label("Freq:     "), Span::raw(optional(info.frequency.map(|f| format!("#{}", f))))]),
+    ];
     
-    let search_input = Paragraph::new(vec![
-        Line::from(search_text),
-        Line::from(Span::styled(help_text, Style::default().fg(Color::DarkGray))),
-    ])
-    .block(
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Kanji {}/{}", position + 1, total))
+                .border_style(Style::default().fg(Color::White)),
+        )
+        .wrap(ratatui::widgets::Wrap { trim: true })
+}
+
+const EXAMPLES_SHOWN: usize = 3;
+
+fn examples_panel(examples: &[ExampleSentence]) -> Paragraph<'static> {
+    let lines: Vec<Line> = examples
+        .iter()
+        .flat_map(|example| {
+            let (before, headword, after) = example.split_highlight();
+            [
+                Line::from(vec![
+                    Span::raw(before),
+                    Span::styled(headword, Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+                    Span::raw(after),
+                ]),
+                Line::from(Span::styled(format!("  {}", example.english), Style::default().fg(Color::Green))),
+            ]
+        })
+        .collect();
+
+    Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan)),
+            .title("Examples")
+            .border_style(Style::default().fg(Color::White)),
     )
-    .style(Style::default().fg(Color::White));
-    f.render_widget(search_input, chunks[1]);
 }
 
-fn format_entry(entry: &WordEntry) -> String {
+fn format_entry(entry: &WordEntry, lang: &str) -> String {
     let mut output = String::new();
     
     if !entry.kanji.is_empty() {
@@ -362,9 +1068,10 @@ fn format_entry(entry: &WordEntry) -> String {
         output.push_str(&entry.kana.join(", "));
     }
     
-    if !entry.english.is_empty() {
+    let glosses = entry.glosses_in(lang);
+    if !glosses.is_empty() {
         output.push_str(" — ");
-        output.push_str(&entry.english[..entry.english.len().min(3)].join("; "));
+        output.push_str(&glosses[..glosses.len().min(3)].join("; "));
     }
     
     if !entry.pos.is_empty() {
@@ -378,25 +1085,22 @@ fn format_entry(entry: &WordEntry) -> String {
     output
 }
 
-fn live_search() -> Result<()> {
+fn live_search(lang: &str) -> Result<()> {
     let mut stdout = io::stdout();
     
     // Check if we're in an interactive terminal
     if !stdout.is_tty() {
-        eprintln!("Error: Live search mode requires an interactive terminal");
+        log::error!("Live search mode requires an interactive terminal");
         return Ok(());
     }
     
     terminal::enable_raw_mode().map_err(|e| {
-        rustyline::error::ReadlineError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other, 
-            format!("Failed to enable raw mode: {}", e)
-        ))
+        rustyline::error::ReadlineError::Io(std::io::Error::other(format!("Failed to enable raw mode: {}", e)))
     })?;
     
     execute!(stdout, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
     
-    println!("JMDict Live Search - {} words loaded", WORD_COUNT);
+    println!("JMDict Live Search - {} words loaded", dictionary_info().word_count);
     println!("Type to search, Ctrl+C to exit\n");
     
     let mut query = String::new();
@@ -410,7 +1114,7 @@ fn live_search() -> Result<()> {
                 results.clear();
             } else {
                 let start = std::time::Instant::now();
-                results = search_dictionary(&query);
+                results = search_dictionary_in(&query, lang);
                 let duration = start.elapsed();
                 
                 // Clear previous results
@@ -420,7 +1124,7 @@ fn live_search() -> Result<()> {
                 
                 // Show top 10 results
                 for entry in results.iter().take(10) {
-                    println!("{}", format_entry(entry));
+                    println!("{}", format_entry(entry, lang));
                 }
                 
                 if results.len() > 10 {
@@ -435,33 +1139,28 @@ fn live_search() -> Result<()> {
         stdout.flush()?;
         
         // Read input
-        match event::read()? {
-            Event::Key(KeyEvent { code, modifiers, .. }) => {
-                match (code, modifiers) {
-                    (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-                        break;
-                    }
-                    (KeyCode::Char(c), _) => {
-                        query.push(c);
-                        execute!(stdout, cursor::MoveTo(0, 1))?;
-                        print!("Search: {}", query);
-                        stdout.flush()?;
-                    }
-                    (KeyCode::Backspace, _) => {
-                        if !query.is_empty() {
-                            query.pop();
-                            execute!(stdout, cursor::MoveTo(0, 1), terminal::Clear(ClearType::UntilNewLine))?;
-                            print!("Search: {}", query);
-                            stdout.flush()?;
-                        }
-                    }
-                    (KeyCode::Enter, _) => {
-                        // Enter doesn't do anything special in live search, just continue
-                    }
-                    _ => {}
+        if let Event::Key(KeyEvent { code, modifiers, .. }) = event::read()? {
+            match (code, modifiers) {
+                (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                    break;
+                }
+                (KeyCode::Char(c), _) => {
+                    query.push(c);
+                    execute!(stdout, cursor::MoveTo(0, 1))?;
+                    print!("Search: {}", query);
+                    stdout.flush()?;
+                }
+                (KeyCode::Backspace, _) if !query.is_empty() => {
+                    query.pop();
+                    execute!(stdout, cursor::MoveTo(0, 1), terminal::Clear(ClearType::UntilNewLine))?;
+                    print!("Search: {}", query);
+                    stdout.flush()?;
                 }
+                (KeyCode::Enter, _) => {
+                    // Enter doesn't do anything special in live search, just continue
+                }
+                _ => {}
             }
-            _ => {}
         }
     }
     
@@ -470,42 +1169,135 @@ fn live_search() -> Result<()> {
     Ok(())
 }
 
+/// Diagnostics go to stderr through `log`, so stdout only carries results.
+/// `default` is the level without `--quiet` or `--verbose`; RUST_LOG
+/// overrides whichever level they pick.
+fn init_logging(quiet: bool, verbose: u8, default: log::LevelFilter) {
+    let level = match (quiet, verbose) {
+        (true, _) => log::LevelFilter::Error,
+        (false, 0) => default,
+        (false, 1) => log::LevelFilter::Info,
+        (false, _) => log::LevelFilter::Debug,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .format(|buf, record| writeln!(buf, "{}: {}", record.level().as_str().to_lowercase(), record.args()))
+        .init();
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
-    
-    // Build search indices on startup for fast searches
-    print!("Building search indices... ");
-    std::io::stdout().flush().unwrap();
-    let start = std::time::Instant::now();
-    japandict_core::search::build_search_indices();
-    println!("done in {:?}", start.elapsed());
-    
-    // TUI mode with ratatui
-    if args.tui {
-        return run_tui();
-    }
+    let cli = Cli::parse();
+    // The server logs what it's listening on, so show that by default
+    let default_level = match cli.command {
+        Some(Command::Serve(_)) => log::LevelFilter::Info,
+        _ => log::LevelFilter::Warn,
+    };
+    init_logging(cli.global.quiet, cli.global.verbose, default_level);
     
-    // Live search mode  
-    if args.live {
-        return live_search();
+    let shorthand = &cli.search;
+    if cli.command.is_some() && (!shorthand.query.is_empty() || shorthand.names || shorthand.results.limit.is_some() || shorthand.results.format.is_some()) {
+        Cli::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, "search options go after the command, e.g. `japandict entry 1358280 --format json`")
+            .exit();
     }
     
-    println!("JMDict CLI - {} words loaded", WORD_COUNT);
-    println!("Dictionary contains {} kanji, {} kana, {} english terms", 
-        KANJI_STRINGS_COUNT, KANA_STRINGS_COUNT, ENGLISH_STRINGS_COUNT);
-    println!();
+    // With no command: a query searches, a piped word list is looked up line
+    // by line, and otherwise the REPL starts
+    let command = cli.command.unwrap_or_else(|| {
+        if cli.version_data || cli.validate_data {
+            Command::Info(InfoArgs { validate: cli.validate_data })
+        } else if !cli.search.query.is_empty() {
+            Command::Search(cli.search)
+        } else if !io::stdin().is_tty() {
+            Command::Batch(BatchArgs { file: PathBuf::from("-"), results: cli.search.results })
+        } else {
+            Command::Repl(ReplArgs { names: cli.search.names, results: cli.search.results })
+        }
+    });
     
-    // If query provided and not interactive mode, search and exit
-    if !args.query.is_empty() && !args.interactive {
-        let query = args.query.join(" ");
-        search_and_display(&query, args.limit);
-        return Ok(());
+    let lang = cli.global.lang.unwrap_or_else(|| default_language().to_string());
+    if !languages().contains(&lang.as_str()) {
+        log::warn!("No '{}' glosses in the dictionary data (available: {})", lang, languages().join(", "));
     }
     
-    // Interactive mode with readline
+    match command {
+        Command::Entry(args) => exit_on_error(commands::entry(&args.id, &lang, args.format)),
+        Command::Kanji(args) => exit_on_error(commands::kanji(&args.text.join(""), args.format)),
+        Command::Export(args) => exit_on_error(commands::export(&lang, args.format)),
+        Command::Info(args) => commands::info(args.validate),
+        Command::Serve(args) => {
+            let options = ServeOptions {
+                host: args.host,
+                port: args.port,
+                lang,
+                threads: args.threads.unwrap_or_else(default_threads),
+            };
+            if let Err(err) = japandict_server::serve(options) {
+                log::error!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        Command::Completions(args) => {
+            // Buffered so a closed pipe is an error rather than a panic inside clap_complete
+            let mut script = Vec::new();
+            clap_complete::generate(args.shell, &mut Cli::command(), "japandict", &mut script);
+            exit_on_error(stdout().write_all(&script));
+        }
+        Command::Man => exit_on_error(clap_mangen::Man::new(Cli::command()).render(&mut stdout())),
+        command => {
+            // Everything else searches, so build the indices once up front
+            let start = std::time::Instant::now();
+            japandict_core::search::build_search_indices();
+            log::info!("Built search indices in {:?}", start.elapsed());
+            let info = dictionary_info();
+            log::info!("{} words, {} kanji and {} names loaded ({})",
+                info.word_count, info.kanji_count, info.name_count, info.languages.join(", "));
+            return run_search_command(command, &lang);
+        }
+    }
+    Ok(())
+}
+
+fn run_search_command(command: Command, lang: &str) -> Result<()> {
+    match command {
+        Command::Search(args) => {
+            if args.query.is_empty() {
+                log::error!("Nothing to search for");
+                std::process::exit(2);
+            }
+            let format = args.results.format.unwrap_or(OutputFormat::Pretty);
+            search_and_print(&args.query.join(" "), args.results.limit.unwrap_or(10), args.names, lang, format);
+        }
+        Command::Batch(args) => {
+            let format = args.results.format.unwrap_or(OutputFormat::Tsv);
+            exit_on_error(run_batch(&args.file, args.results.limit.unwrap_or(1), lang, format));
+        }
+        Command::Repl(args) => {
+            let format = args.results.format.unwrap_or(OutputFormat::Pretty);
+            return repl(args.results.limit.unwrap_or(10), args.names, lang, format);
+        }
+        Command::Tui(args) => return run_tui(args.names, lang.to_string()),
+        Command::Live => return live_search(lang),
+        Command::Parse(args) => {
+            exit_on_error(commands::text_or_stdin(&args.text).and_then(|text| commands::parse(&text, lang, args.format)));
+        }
+        Command::Annotate(args) => {
+            exit_on_error(commands::text_or_stdin(&args.text).and_then(|text| commands::annotate(&text)));
+        }
+        _ => unreachable!("handled in main"),
+    }
+    Ok(())
+}
+
+/// Prompt for queries until Ctrl+C, Ctrl+D, `quit` or `exit`
+fn repl(limit: usize, include_names: bool, lang: &str, format: OutputFormat) -> Result<()> {
+    let pretty = format == OutputFormat::Pretty;
     let mut rl: Editor<(), _> = Editor::new()?;
     
-    println!("Interactive mode - type Japanese or English to search (Ctrl+C to exit)");
+    if pretty {
+        println!("Interactive mode - type Japanese or English to search (Ctrl+C to exit)");
+    }
     
     loop {
         let readline = rl.readline("dict> ");
@@ -515,14 +1307,18 @@ fn main() -> Result<()> {
                     break;
                 }
                 rl.add_history_entry(line.as_str())?;
-                search_and_display(&line, args.limit);
+                if !line.trim().is_empty() {
+                    search_and_print(&line, limit, include_names, lang, format);
+                }
             }
             Err(_) => {
-                println!("Goodbye!");
+                if pretty {
+                    println!("Goodbye!");
+                }
                 break;
             }
         }
     }
     
     Ok(())
-}
\ No newline at end of file
+}
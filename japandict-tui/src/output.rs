@@ -0,0 +1,511 @@
+//! Machine-readable output for scripts (`--format`)
+//!
+//! Everything goes to stdout with no banner, timings or decoration, so the
+//! same query against the same data always prints the same bytes.
+
+use clap::ValueEnum;
+use japandict_core::{components_of, get_word_entry, KanjiInfo, NameEntry, SearchResult, Token, WordEntry};
+use serde::Serialize;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Decorated text for people (the default)
+    Pretty,
+    /// One line per entry: forms, readings, parts of speech and numbered senses
+    Plain,
+    /// A single JSON document with every result
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    /// Tab-separated columns: id, score, kanji, kana, pos, common, glosses
+    Tsv,
+}
+
+#[derive(Serialize)]
+struct GlossRecord {
+    lang: &'static str,
+    /// JMdict sense the gloss belongs to, counting from 0
+    sense: u8,
+    text: &'static str,
+}
+
+#[derive(Serialize)]
+struct WordRecord {
+    kind: &'static str,
+    id: &'static str,
+    /// Search score; absent outside search results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<f32>,
+    kanji: Vec<&'static str>,
+    kana: Vec<&'static str>,
+    glosses: Vec<GlossRecord>,
+    pos: Vec<&'static str>,
+    common: bool,
+}
+
+impl WordRecord {
+    fn new(entry: &WordEntry, score: Option<f32>) -> WordRecord {
+        WordRecord {
+            kind: "word",
+            id: entry.id,
+            score,
+            kanji: entry.kanji.clone(),
+            kana: entry.kana.clone(),
+            glosses: entry
+                .glosses
+                .iter()
+                .map(|gloss| GlossRecord { lang: gloss.lang, sense: gloss.sense, text: gloss.text })
+                .collect(),
+            pos: entry.pos.clone(),
+            common: entry.is_common,
+        }
+    }
+
+    fn from_result(result: &SearchResult) -> WordRecord {
+        WordRecord::new(&result.entry, Some(result.score))
+    }
+}
+
+#[derive(Serialize)]
+struct NameRecord {
+    kind: &'static str,
+    id: &'static str,
+    kanji: Vec<&'static str>,
+    kana: Vec<&'static str>,
+    translations: Vec<&'static str>,
+    name_types: Vec<&'static str>,
+}
+
+impl NameRecord {
+    fn new(entry: &NameEntry) -> NameRecord {
+        NameRecord {
+            kind: "name",
+            id: entry.id,
+            kanji: entry.kanji.clone(),
+            kana: entry.kana.clone(),
+            translations: entry.translations.clone(),
+            name_types: entry.name_types.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    query: &'a str,
+    lang: &'a str,
+    results: Vec<WordRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    names: Option<Vec<NameRecord>>,
+}
+
+/// Print word results (and names, when `names` is given) in a
+/// machine-readable `format`. Glosses in `plain` and `tsv` are in `lang`; JSON
+/// carries every language.
+pub fn write_results(
+    out: &mut impl Write,
+    format: OutputFormat,
+    query: &str,
+    lang: &str,
+    results: &[SearchResult],
+    names: Option<&[NameEntry]>,
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Pretty => unreachable!("pretty output is printed by search_and_display"),
+        OutputFormat::Json => {
+            let document = Document {
+                query,
+                lang,
+                results: results.iter().map(WordRecord::from_result).collect(),
+                names: names.map(|names| names.iter().map(NameRecord::new).collect()),
+            };
+            serde_json::to_writer(&mut *out, &document)?;
+            writeln!(out)
+        }
+        OutputFormat::Jsonl => {
+            for result in results {
+                serde_json::to_writer(&mut *out, &WordRecord::from_result(result))?;
+                writeln!(out)?;
+            }
+            for name in names.unwrap_or_default() {
+                serde_json::to_writer(&mut *out, &NameRecord::new(name))?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Tsv => {
+            for result in results {
+                writeln!(out, "{}", tsv_row(&result.entry, Some(result.score), lang))?;
+            }
+            for name in names.unwrap_or_default() {
+                writeln!(
+                    out,
+                    "{}\t\t{}\t{}\t{}\t0\t{}",
+                    name.id,
+                    tsv_field(&name.kanji.join(",")),
+                    tsv_field(&name.kana.join(",")),
+                    tsv_field(&name.name_types.join(",")),
+                    tsv_field(&name.translations.join("; ")),
+                )?;
+            }
+            Ok(())
+        }
+        OutputFormat::Plain => {
+            for result in results {
+                writeln!(out, "{}", plain_line(&result.entry, lang))?;
+            }
+            for name in names.unwrap_or_default() {
+                let mut line = forms(&name.kanji, &name.kana);
+                if !name.name_types.is_empty() {
+                    line.push_str(&format!(" [{}]", name.name_types.join(", ")));
+                }
+                if !name.translations.is_empty() {
+                    line.push_str(&format!(" {}", name.translations.join("; ")));
+                }
+                writeln!(out, "{}", line)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes one record per query for batch lookups, streaming as queries come
+/// in. Every query gets output, so unresolved ones are visible:
+/// - `tsv`: one row per result, prefixed with the query and a found column
+///   (1/0); a query with no results gets a row with only those two filled in
+/// - `jsonl`: one `{"query", "found", "results"}` object per query
+/// - `json`: the same objects in one array
+/// - `plain`: `query: entry` per result, or `query: no match`
+pub struct BatchWriter<W: Write> {
+    out: W,
+    format: OutputFormat,
+    lang: String,
+    written: usize,
+}
+
+#[derive(Serialize)]
+struct BatchRecord<'a> {
+    query: &'a str,
+    found: bool,
+    results: Vec<WordRecord>,
+}
+
+impl<W: Write> BatchWriter<W> {
+    pub fn new(out: W, format: OutputFormat, lang: &str) -> BatchWriter<W> {
+        BatchWriter { out, format, lang: lang.to_string(), written: 0 }
+    }
+
+    pub fn write(&mut self, query: &str, results: &[SearchResult]) -> io::Result<()> {
+        let out = &mut self.out;
+        match self.format {
+            OutputFormat::Pretty => unreachable!("pretty output is printed by search_and_display"),
+            OutputFormat::Json | OutputFormat::Jsonl => {
+                let record = BatchRecord { query, found: !results.is_empty(), results: results.iter().map(WordRecord::from_result).collect() };
+                if self.format == OutputFormat::Json {
+                    out.write_all(if self.written == 0 { b"[" } else { b"," })?;
+                    serde_json::to_writer(&mut *out, &record)?;
+                } else {
+                    serde_json::to_writer(&mut *out, &record)?;
+                    writeln!(out)?;
+                }
+            }
+            OutputFormat::Tsv => {
+                if results.is_empty() {
+                    writeln!(out, "{}\t0\t\t\t\t\t\t\t", tsv_field(query))?;
+                }
+                for result in results {
+                    writeln!(out, "{}\t1\t{}", tsv_field(query), tsv_row(&result.entry, Some(result.score), &self.lang))?;
+                }
+            }
+            OutputFormat::Plain => {
+                if results.is_empty() {
+                    writeln!(out, "{}: no match", query)?;
+                }
+                for result in results {
+                    writeln!(out, "{}: {}", query, plain_line(&result.entry, &self.lang))?;
+                }
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close the JSON array and flush
+    pub fn finish(mut self) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            let close: &[u8] = if self.written == 0 { b"[]\n" } else { b"]\n" };
+            self.out.write_all(close)?;
+        }
+        self.out.flush()
+    }
+}
+
+/// Print whole entries, as `export` and `entry` do: a JSON array, one object
+/// per line, or one `tsv`/`plain` row each with glosses in `lang`
+pub fn write_entries(
+    out: &mut impl Write,
+    format: OutputFormat,
+    lang: &str,
+    entries: impl IntoIterator<Item = WordEntry>,
+) -> io::Result<()> {
+    let mut written = 0;
+    for entry in entries {
+        match format {
+            OutputFormat::Pretty => unreachable!("pretty entries are printed by commands::print_entry"),
+            OutputFormat::Json => {
+                out.write_all(if written == 0 { b"[" } else { b"," })?;
+                serde_json::to_writer(&mut *out, &WordRecord::new(&entry, None))?;
+            }
+            OutputFormat::Jsonl => {
+                serde_json::to_writer(&mut *out, &WordRecord::new(&entry, None))?;
+                writeln!(out)?;
+            }
+            OutputFormat::Tsv => writeln!(out, "{}", tsv_row(&entry, None, lang))?,
+            OutputFormat::Plain => writeln!(out, "{}", plain_line(&entry, lang))?,
+        }
+        written += 1;
+    }
+    if format == OutputFormat::Json {
+        out.write_all(if written == 0 { b"[]\n" } else { b"]\n" })?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct KanjiRecord {
+    literal: char,
+    on_readings: Vec<&'static str>,
+    kun_readings: Vec<&'static str>,
+    meanings: Vec<&'static str>,
+    stroke_count: u8,
+    grade: Option<u8>,
+    jlpt: Option<u8>,
+    frequency: Option<u16>,
+    radical: u8,
+    components: Vec<char>,
+}
+
+impl KanjiRecord {
+    fn new(info: &KanjiInfo) -> KanjiRecord {
+        KanjiRecord {
+            literal: info.literal,
+            on_readings: info.on_readings.clone(),
+            kun_readings: info.kun_readings.clone(),
+            meanings: info.meanings.clone(),
+            stroke_count: info.stroke_count,
+            grade: info.grade,
+            jlpt: info.jlpt,
+            frequency: info.frequency,
+            radical: info.radical,
+            components: components_of(info.literal),
+        }
+    }
+}
+
+/// Print KANJIDIC2 entries. TSV columns are literal, on, kun, meanings,
+/// strokes, radical number, grade, JLPT level and frequency rank.
+pub fn write_kanji(out: &mut impl Write, format: OutputFormat, kanji: &[KanjiInfo]) -> io::Result<()> {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    match format {
+        OutputFormat::Pretty => unreachable!("pretty kanji are printed by commands::kanji"),
+        OutputFormat::Json => {
+            serde_json::to_writer(&mut *out, &kanji.iter().map(KanjiRecord::new).collect::<Vec<_>>())?;
+            writeln!(out)
+        }
+        OutputFormat::Jsonl => {
+            for info in kanji {
+                serde_json::to_writer(&mut *out, &KanjiRecord::new(info))?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Tsv => {
+            for info in kanji {
+                writeln!(
+                    out,
+                    "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                    info.literal,
+                    tsv_field(&info.on_readings.join(",")),
+                    tsv_field(&info.kun_readings.join(",")),
+                    tsv_field(&info.meanings.join("; ")),
+                    info.stroke_count,
+                    info.radical,
+                    optional(info.grade.map(|grade| grade.to_string())),
+                    optional(info.jlpt.map(|jlpt| jlpt.to_string())),
+                    optional(info.frequency.map(|frequency| frequency.to_string())),
+                )?;
+            }
+            Ok(())
+        }
+        OutputFormat::Plain => {
+            for info in kanji {
+                let readings: Vec<&str> = info.on_readings.iter().chain(&info.kun_readings).copied().collect();
+                writeln!(out, "{} [{}] {} ({} strokes)", info.literal, readings.join(", "), info.meanings.join("; "), info.stroke_count)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TokenRecord<'a> {
+    text: &'a str,
+    /// Byte offset of `text` in the input
+    start: usize,
+    entries: Vec<WordRecord>,
+}
+
+impl TokenRecord<'_> {
+    fn new<'a>(token: &Token<'a>) -> TokenRecord<'a> {
+        TokenRecord {
+            text: token.text,
+            start: token.start,
+            entries: token.entries.iter().map(|&index| WordRecord::new(&get_word_entry(index), None)).collect(),
+        }
+    }
+}
+
+/// Print the tokens of parsed text. JSON lists every matching entry; `tsv`
+/// (text, start, then the entry columns) and `plain` show the first one, and
+/// text without a match gets a row of its own.
+pub fn write_tokens(out: &mut impl Write, format: OutputFormat, lang: &str, tokens: &[Token]) -> io::Result<()> {
+    match format {
+        OutputFormat::Pretty => unreachable!("pretty tokens are printed by commands::parse"),
+        OutputFormat::Json => {
+            serde_json::to_writer(&mut *out, &tokens.iter().map(TokenRecord::new).collect::<Vec<_>>())?;
+            writeln!(out)
+        }
+        OutputFormat::Jsonl => {
+            for token in tokens {
+                serde_json::to_writer(&mut *out, &TokenRecord::new(token))?;
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        OutputFormat::Tsv => {
+            for token in tokens {
+                match token.entries.first() {
+                    Some(&index) => writeln!(out, "{}\t{}\t{}", tsv_field(token.text), token.start, tsv_row(&get_word_entry(index), None, lang))?,
+                    None => writeln!(out, "{}\t{}\t\t\t\t\t\t\t", tsv_field(token.text), token.start)?,
+                }
+            }
+            Ok(())
+        }
+        OutputFormat::Plain => {
+            for token in tokens {
+                match token.entries.first() {
+                    Some(&index) => writeln!(out, "{}: {}", token.text, plain_line(&get_word_entry(index), lang))?,
+                    None => writeln!(out, "{}", token.text)?,
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// id, score, kanji, kana, pos, common (1/0) and the glosses in `lang`, with
+/// senses separated by " | ". The score column is empty outside search results.
+fn tsv_row(entry: &WordEntry, score: Option<f32>, lang: &str) -> String {
+    let glosses: Vec<String> = entry.senses_in(lang).iter().map(|glosses| glosses.join("; ")).collect();
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        entry.id,
+        score.map_or(String::new(), |score| score.to_string()),
+        tsv_field(&entry.kanji.join(",")),
+        tsv_field(&entry.kana.join(",")),
+        tsv_field(&entry.pos.join(",")),
+        if entry.is_common { 1 } else { 0 },
+        tsv_field(&glosses.join(" | ")),
+    )
+}
+
+/// Forms, parts of speech and numbered senses on one line
+fn plain_line(entry: &WordEntry, lang: &str) -> String {
+    let mut line = forms(&entry.kanji, &entry.kana);
+    if !entry.pos.is_empty() {
+        line.push_str(&format!(" [{}]", entry.pos.join(", ")));
+    }
+    for (i, glosses) in entry.senses_in(lang).iter().enumerate() {
+        line.push_str(&format!(" {}. {}", i + 1, glosses.join("; ")));
+    }
+    if entry.is_common {
+        line.push_str(" (common)");
+    }
+    line
+}
+
+/// "漢字, 感じ (かんじ)", or just the readings for kana-only words
+fn forms(kanji: &[&str], kana: &[&str]) -> String {
+    match (kanji.is_empty(), kana.is_empty()) {
+        (true, _) => kana.join(", "),
+        (false, true) => kanji.join(", "),
+        (false, false) => format!("{} ({})", kanji.join(", "), kana.join(", ")),
+    }
+}
+
+/// Tabs and line breaks would split the row
+fn tsv_field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use japandict_core::Gloss;
+
+    fn entry(kanji: Vec<&'static str>, glosses: &[(&'static str, u8)]) -> WordEntry {
+        WordEntry {
+            id: "1000000",
+            kanji,
+            kana: vec!["いぬ"],
+            english: glosses.iter().map(|&(text, _)| text).collect(),
+            glosses: glosses.iter().map(|&(text, sense)| Gloss { lang: "eng", text, sense }).collect(),
+            pos: vec!["n"],
+            is_common: true,
+            accents: vec![vec![2]],
+        }
+    }
+
+    #[test]
+    fn tsv_rows_keep_their_columns() {
+        let rows = [
+            tsv_row(&entry(vec!["犬", "狗"], &[("dog", 0), ("hound", 0), ("spy", 1)]), Some(1.5), "eng"),
+            tsv_row(&entry(Vec::new(), &[]), None, "eng"),
+            tsv_row(&entry(vec!["犬"], &[("dog", 0)]), None, "ger"),
+        ];
+        for row in &rows {
+            assert_eq!(row.split('\t').count(), 7, "{:?}", row);
+        }
+        assert_eq!(rows[0], "1000000\t1.5\t犬,狗\tいぬ\tn\t1\tdog; hound | spy");
+        assert_eq!(rows[1], "1000000\t\t\tいぬ\tn\t1\t");
+    }
+
+    #[test]
+    fn tsv_fields_escape_tabs_and_newlines() {
+        let row = tsv_row(&entry(vec!["犬"], &[("dog\tcanine", 0), ("spy\r\nsnoop", 1)]), None, "eng");
+        assert_eq!(row.split('\t').count(), 7);
+        assert!(!row.contains(['\n', '\r']));
+        assert!(row.ends_with("\tdog canine | spy  snoop"));
+    }
+
+    fn batch_output(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        let mut writer = BatchWriter::new(&mut out, format, "eng");
+        writer.write("", &[]).unwrap();
+        writer.write("", &[]).unwrap();
+        writer.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn blank_batch_lines_get_an_empty_result() {
+        let empty = r#"{"query":"","found":false,"results":[]}"#;
+        assert_eq!(batch_output(OutputFormat::Json), format!("[{},{}]\n", empty, empty));
+        assert_eq!(batch_output(OutputFormat::Jsonl), format!("{}\n{}\n", empty, empty));
+        // The query and found columns, then empty entry columns
+        let tsv = batch_output(OutputFormat::Tsv);
+        assert_eq!(tsv, "\t0\t\t\t\t\t\t\t\n".repeat(2));
+        assert!(tsv.lines().all(|row| row.split('\t').count() == 2 + 7));
+        assert_eq!(batch_output(OutputFormat::Plain), ": no match\n".repeat(2));
+    }
+}
@@ -0,0 +1,19 @@
+//! Names the dictionary cache after the dictionary file, so clients drop
+//! their cached copy whenever `make codegen-web` writes different data
+
+use std::fs;
+
+const DATA_FILE: &str = "public/dictionary.bin.gz";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", DATA_FILE);
+    // FNV-1a, as jmdict-codegen's string pool uses; a missing file (before
+    // `make codegen-web`) gets a fixed name
+    let hash = match fs::read(DATA_FILE) {
+        Ok(bytes) => format!("{:016x}", bytes.iter().fold(0xcbf29ce484222325u64, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })),
+        Err(_) => "none".to_string(),
+    };
+    println!("cargo:rustc-env=JAPANDICT_DATA_HASH={}", hash);
+}
@@ -0,0 +1,85 @@
+// Service worker: keeps the app and its dictionary available offline.
+// The page registers it as `sw.js?cache=<name>`, passing the cache named
+// after the dictionary file the dictionary loader (src/data.rs) reads, so
+// both share one copy of the dictionary and new data installs a fresh worker.
+const CACHE_PREFIX = 'japandict-data-';
+const CACHE = new URL(self.location).searchParams.get('cache') || CACHE_PREFIX + 'default';
+const DATA_URL = './dictionary.bin.gz';
+
+// The bindings sit under assets/dioxus/ with `dx`, next to the page otherwise;
+// whichever layout is missing just fails to cache
+const APP_SHELL = [
+    './',
+    './manifest.webmanifest',
+    './icon.svg',
+    './tailwind.css',
+    './worker.js',
+    './assets/dioxus/japandict-web.js',
+    './assets/dioxus/japandict-web_bg.wasm',
+    './japandict-web.js',
+    './japandict-web_bg.wasm',
+];
+
+self.addEventListener('install', (event) => {
+    event.waitUntil((async () => {
+        const cache = await caches.open(CACHE);
+        await Promise.allSettled(APP_SHELL.map((url) => cache.add(url)));
+        // The loader may already have stored the dictionary in this cache
+        if (!(await cache.match(DATA_URL))) {
+            await cache.add(DATA_URL);
+        }
+        await self.skipWaiting();
+    })());
+});
+
+self.addEventListener('activate', (event) => {
+    event.waitUntil((async () => {
+        const names = await caches.keys();
+        await Promise.all(names
+            .filter((name) => name.startsWith(CACHE_PREFIX) && name !== CACHE)
+            .map((name) => caches.delete(name)));
+        await self.clients.claim();
+    })());
+});
+
+self.addEventListener('fetch', (event) => {
+    const url = new URL(event.request.url);
+    if (event.request.method !== 'GET' || url.origin !== self.location.origin) {
+        return;
+    }
+    // New dictionary data comes with a new cache name
+    if (url.pathname.endsWith('/dictionary.bin.gz')) {
+        event.respondWith(cacheFirst(event.request));
+    } else {
+        event.respondWith(networkFirst(event.request));
+    }
+});
+
+async function cacheFirst(request) {
+    const cached = await caches.match(request);
+    return cached || fetch(request);
+}
+
+// The app shell stays current when online and falls back to the cache offline
+async function networkFirst(request) {
+    const cache = await caches.open(CACHE);
+    try {
+        const response = await fetch(request);
+        if (response.ok) {
+            cache.put(request, response.clone());
+        }
+        return response;
+    } catch (err) {
+        const cached = await cache.match(request, { ignoreSearch: true });
+        if (cached) {
+            return cached;
+        }
+        if (request.mode === 'navigate') {
+            const shell = await cache.match('./');
+            if (shell) {
+                return shell;
+            }
+        }
+        throw err;
+    }
+}
@@ -0,0 +1,8 @@
+// Search worker: loads the app's wasm module, whose `main` detects that it is
+// running in a worker and serves searches instead of rendering the UI.
+// The bindings sit under assets/dioxus/ with `dx`, next to the page otherwise.
+const bindings = await import('./assets/dioxus/japandict-web.js').catch(() => import('./japandict-web.js'));
+const wasm = await bindings.default();
+if (wasm.__wbindgen_start === undefined) {
+    wasm.main();
+}
@@ -0,0 +1,101 @@
+//! Fetching the dictionary at runtime
+//!
+//! The web build doesn't embed the dictionary in the wasm binary: `make
+//! codegen-web` writes it gzip-compressed next to the page, and the page and
+//! the search worker each load it after startup. The compressed file is kept in
+//! Cache Storage, so later visits (and the worker, right after the page) don't
+//! download it again.
+
+use japandict_core::Dictionary;
+use js_sys::{Array, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Cache, CacheStorage, Response};
+
+/// Dictionary file, relative to the page
+const DATA_URL: &str = "./dictionary.bin.gz";
+
+/// Named after a hash of the dictionary file (see build.rs), so new data gets
+/// a new cache. Caches from other builds are deleted once this one is filled.
+/// The service worker (see `offline`) keeps the app shell in the same cache.
+const CACHE_PREFIX: &str = "japandict-data-";
+pub const CACHE_NAME: &str = concat!("japandict-data-", env!("JAPANDICT_DATA_HASH"));
+
+#[wasm_bindgen]
+extern "C" {
+    /// `fetch` on the global scope, which is a window or a worker
+    #[wasm_bindgen(js_name = fetch)]
+    fn global_fetch(url: &str) -> js_sys::Promise;
+
+    /// Not in web-sys yet
+    type DecompressionStream;
+
+    #[wasm_bindgen(constructor, catch)]
+    fn new(format: &str) -> Result<DecompressionStream, JsValue>;
+}
+
+/// Download (or read from the cache) and parse the dictionary file
+pub async fn load_dictionary() -> Result<Dictionary, String> {
+    let response = cached_response().await?;
+    let bytes = decompress(response).await?;
+    Dictionary::from_bytes(bytes).map_err(|err| err.to_string())
+}
+
+async fn cached_response() -> Result<Response, String> {
+    // Cache Storage only exists in secure contexts
+    let Some(caches) = cache_storage() else {
+        return fetch().await;
+    };
+    let cache: Cache = JsFuture::from(caches.open(CACHE_NAME)).await.map_err(js_error)?.unchecked_into();
+    if let Ok(response) = JsFuture::from(cache.match_with_str(DATA_URL)).await.map_err(js_error)?.dyn_into::<Response>() {
+        return Ok(response);
+    }
+
+    let response = fetch().await?;
+    let copy = response.clone().map_err(js_error)?;
+    if let Err(err) = JsFuture::from(cache.put_with_str(DATA_URL, &copy)).await {
+        log::warn!("Failed to cache the dictionary: {}", js_error(err));
+    }
+    remove_old_caches(&caches).await;
+    Ok(response)
+}
+
+fn cache_storage() -> Option<CacheStorage> {
+    Reflect::get(&js_sys::global(), &JsValue::from_str("caches")).ok()?.dyn_into().ok()
+}
+
+async fn fetch() -> Result<Response, String> {
+    let response: Response = JsFuture::from(global_fetch(DATA_URL)).await.map_err(js_error)?.unchecked_into();
+    if !response.ok() {
+        return Err(format!("Failed to download {}: HTTP {}", DATA_URL, response.status()));
+    }
+    Ok(response)
+}
+
+async fn remove_old_caches(caches: &CacheStorage) {
+    let Ok(names) = JsFuture::from(caches.keys()).await else {
+        return;
+    };
+    for name in Array::from(&names).iter().filter_map(|name| name.as_string()) {
+        if name.starts_with(CACHE_PREFIX) && name != CACHE_NAME {
+            let _ = JsFuture::from(caches.delete(&name)).await;
+        }
+    }
+}
+
+async fn decompress(response: Response) -> Result<Vec<u8>, String> {
+    let body = response.body().ok_or("The dictionary response has no body")?;
+    let gunzip = DecompressionStream::new("gzip").map_err(js_error)?;
+    let stream = body.pipe_through(gunzip.unchecked_ref());
+    let decompressed = Response::new_with_opt_readable_stream(Some(&stream)).map_err(js_error)?;
+    let buffer = JsFuture::from(decompressed.array_buffer().map_err(js_error)?).await.map_err(js_error)?;
+    Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+fn js_error(err: JsValue) -> String {
+    match err.dyn_ref::<js_sys::Error>() {
+        Some(err) => err.message().into(),
+        None => format!("{:?}", err),
+    }
+}
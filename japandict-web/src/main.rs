@@ -1,23 +1,212 @@
+mod data;
+mod offline;
+mod worker;
+
 use dioxus::prelude::*;
-use japandict_core::{search_dictionary, WordEntry};
+use dioxus_router::prelude::*;
+use futures_util::StreamExt;
+use japandict_core::{
+    compatible_radicals, components_of, default_language, dictionary_info, examples_for, get_by_jmdict_id, get_kanji,
+    cross_references, get_name_entry, get_word_entry, is_installed, kanji_by_components, languages, pitch, radicals, ExampleSentence,
+    KanjiInfo, NameEntry, WordEntry, XrefKind, SEARCH_INDEX_COUNT,
+};
+use std::fmt;
+use worker::{SearchWorker, WorkerEvent};
 
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     console_error_panic_hook::set_once();
     
-    // Build search indices on startup for fast searches
-    japandict_core::search::build_search_indices();
+    // The same module runs the search worker, which builds the indices off the page
+    if worker::is_worker() {
+        worker::serve();
+        return;
+    }
     
+    offline::register_service_worker();
     launch(App);
 }
 
+/// Where the page is in getting the dictionary ready to search
+#[derive(Clone, Debug, PartialEq)]
+enum DataStatus {
+    /// Downloading the dictionary file, or reading it from the cache
+    Loading,
+    /// `done` of `total` search indices are built in the worker
+    Indexing { done: usize, total: usize },
+    Ready,
+    Failed(String),
+}
+
+impl DataStatus {
+    /// Whether the page has the data, so entries can be shown
+    fn is_loaded(&self) -> bool {
+        matches!(self, DataStatus::Indexing { .. } | DataStatus::Ready)
+    }
+}
+
+#[derive(Clone, Routable, Debug, PartialEq)]
+#[rustfmt::skip]
+enum Route {
+    #[layout(Shell)]
+        #[route("/")]
+        Home {},
+        #[route("/search?:..query")]
+        Search { query: SearchQuery },
+        #[route("/entry/:id")]
+        Entry { id: String },
+}
+
+/// The `q=…` query of a search URL, percent-encoded so any text round-trips
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SearchQuery {
+    q: String,
+}
+
+impl FromQuery for SearchQuery {
+    fn from_query(query: &str) -> Self {
+        let q = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("q="))
+            .and_then(|q| js_sys::decode_uri_component(&q.replace('+', " ")).ok())
+            .map(String::from)
+            .unwrap_or_default();
+        SearchQuery { q }
+    }
+}
+
+impl fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "q={}", js_sys::encode_uri_component(&self.q))
+    }
+}
+
+/// Route showing the results for `q`, or the start page when it's blank
+fn search_route(q: String) -> Route {
+    if q.trim().is_empty() {
+        Route::Home {}
+    } else {
+        Route::Search { query: SearchQuery { q } }
+    }
+}
+
+/// Path the app is served under, taken from the page's `<base href>`, so
+/// routes work when the site isn't at the root of its domain
+fn base_path() -> Option<String> {
+    let base = web_sys::window()?.document()?.base_uri().ok()??;
+    let path = web_sys::Url::new(&base).ok()?.pathname();
+    let path = path.trim_end_matches('/');
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// State shared by the routes, provided by `App`
+#[derive(Clone, Copy)]
+struct AppState {
+    query: Signal<String>,
+    results: Signal<Vec<WordEntry>>,
+    names: Signal<Vec<NameEntry>>,
+    include_names: Signal<bool>,
+    lang: Signal<String>,
+    /// Latest search sent to the worker; older answers are dropped
+    search_id: Signal<u32>,
+    status: Signal<DataStatus>,
+    /// Started once the page has the data, so the worker reads it from the cache
+    search_worker: Signal<Option<SearchWorker>>,
+}
+
+impl AppState {
+    /// Send `q` to the worker, or clear the results when it's blank. Reads
+    /// with `peek`, so callers in effects don't subscribe to the state.
+    fn search(mut self, q: String) {
+        self.query.set(q.clone());
+        *self.search_id.write() += 1;
+        if q.trim().is_empty() {
+            self.results.set(Vec::new());
+            self.names.set(Vec::new());
+            return;
+        }
+        if let Some(worker) = self.search_worker.peek().as_ref() {
+            worker.search(*self.search_id.peek(), &q, &self.lang.peek(), *self.include_names.peek());
+        }
+    }
+
+    /// Run the current search again, e.g. with a different language
+    fn refresh(self) {
+        let q = self.query.peek().clone();
+        self.search(q);
+    }
+}
+
 #[component]
 fn App() -> Element {
-    let mut query = use_signal(String::new);
-    let mut results = use_signal(Vec::<WordEntry>::new);
-    let mut perform_search = move |q: String| {
-        let search_results = search_dictionary(&q);
-        results.set(search_results);
+    let mut state = use_context_provider(|| AppState {
+        query: Signal::new(String::new()),
+        results: Signal::new(Vec::new()),
+        names: Signal::new(Vec::new()),
+        include_names: Signal::new(false),
+        lang: Signal::new(default_language().to_string()),
+        search_id: Signal::new(0),
+        status: Signal::new(DataStatus::Loading),
+        search_worker: Signal::new(None),
+    });
+    
+    let events = use_coroutine(move |mut rx: UnboundedReceiver<WorkerEvent>| async move {
+        while let Some(event) = rx.next().await {
+            match event {
+                WorkerEvent::Progress { done, total } => state.status.set(DataStatus::Indexing { done, total }),
+                WorkerEvent::Ready => state.status.set(DataStatus::Ready),
+                WorkerEvent::Failed(err) => state.status.set(DataStatus::Failed(err)),
+                WorkerEvent::Results { id, words, names: name_indices } => {
+                    if id == *state.search_id.peek() {
+                        state.results.set(words.into_iter().map(get_word_entry).collect());
+                        state.names.set(name_indices.into_iter().map(get_name_entry).collect());
+                    }
+                }
+            }
+        }
+    });
+    
+    // The page keeps its own copy to render entries, kanji and radicals (see `worker`)
+    use_future(move || async move {
+        let loaded = data::load_dictionary().await.and_then(|dictionary| dictionary.install().map_err(|err| err.to_string()));
+        match loaded {
+            Ok(()) => {
+                state.lang.set(default_language().to_string());
+                state.status.set(DataStatus::Indexing { done: 0, total: SEARCH_INDEX_COUNT });
+                state.search_worker.set(Some(SearchWorker::spawn(move |event| events.send(event))));
+                // Run anything searched for during the download
+                state.refresh();
+            }
+            Err(err) => {
+                log::error!("Failed to load the dictionary: {}", err);
+                state.status.set(DataStatus::Failed(err));
+            }
+        }
+    });
+
+    rsx! {
+        Router::<Route> {
+            config: || RouterConfig::default().history(WebHistory::new(base_path(), true))
+        }
+    }
+}
+
+/// Layout around every route: the search controls, load status and footer
+#[component]
+fn Shell() -> Element {
+    let mut state = use_context::<AppState>();
+    let mut show_radicals = use_signal(|| false);
+    let route = use_route::<Route>();
+    let navigator = navigator();
+    
+    // Typing refines the current search instead of adding a history entry per key
+    let on_search_page = matches!(route, Route::Search { .. });
+    let go_to_search = move |q: String| {
+        if on_search_page {
+            navigator.replace(search_route(q));
+        } else {
+            navigator.push(search_route(q));
+        }
     };
 
     rsx! {
@@ -30,7 +219,10 @@ fn App() -> Element {
                     class: "max-w-4xl mx-auto px-4 py-6",
                     h1 {
                         class: "text-3xl font-bold text-gray-900 flex items-center gap-2",
-                        "🗾 Japanese Dictionary"
+                        Link {
+                            to: Route::Home {},
+                            "🗾 Japanese Dictionary"
+                        }
                     }
                     p {
                         class: "text-gray-600 mt-2",
@@ -43,28 +235,177 @@ fn App() -> Element {
                 class: "max-w-4xl mx-auto px-4 py-8",
                 
                 SearchBox {
-                    query: query.read().clone(),
-                    on_search: move |q: String| {
-                        query.set(q.clone());
-                        if !q.trim().is_empty() {
-                            perform_search(q);
-                        } else {
-                            results.set(Vec::new());
+                    query: state.query.read().clone(),
+                    on_search: move |q: String| go_to_search(q)
+                }
+                
+                div {
+                    class: "-mt-6 mb-6 flex items-center gap-6",
+                    button {
+                        class: "text-sm text-blue-600 hover:text-blue-800",
+                        onclick: move |_| show_radicals.set(!show_radicals()),
+                        if show_radicals() { "Hide radical lookup" } else { "部首 Find a kanji by its radicals" }
+                    }
+                    label {
+                        class: "text-sm text-gray-600 flex items-center gap-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: (state.include_names)(),
+                            onchange: move |_| {
+                                let include = !(state.include_names)();
+                                state.include_names.set(include);
+                                state.refresh();
+                            }
+                        }
+                        "Include names (places, people, companies)"
+                    }
+                    
+                    // Gloss language, when the data was generated with several
+                    if languages().len() > 1 {
+                        label {
+                            class: "text-sm text-gray-600 flex items-center gap-2",
+                            "Definitions in"
+                            select {
+                                class: "border border-gray-300 rounded px-2 py-1",
+                                onchange: move |e| {
+                                    state.lang.set(e.value());
+                                    state.refresh();
+                                },
+{languages().iter().map(|&code| rsx! {
+                                    option {
+                                        value: "{code}",
+                                        selected: code == *state.lang.read(),
+                                        "{language_name(code)}"
+                                    }
+                                })}
+                            }
                         }
                     }
                 }
                 
-                if !results.read().is_empty() {
-                    ResultsSection {
-                        results: results.read().clone(),
-                        query: query.read().clone()
+                // Searches typed meanwhile are answered once the data is ready
+{match (state.status)() {
+                    DataStatus::Loading => rsx! {
+                        div {
+                            class: "mb-6 text-sm text-gray-500",
+                            "Downloading dictionary…"
+                        }
+                    },
+                    DataStatus::Indexing { done, total } => {
+                        let percent = done * 100 / total.max(1);
+                        rsx! {
+                            div {
+                                class: "mb-6 text-sm text-gray-500",
+                                "Building search index… ({done}/{total})"
+                                div {
+                                    class: "mt-1 h-1 bg-gray-200 rounded",
+                                    div {
+                                        class: "h-1 bg-blue-500 rounded transition-all",
+                                        style: "width: {percent}%"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    DataStatus::Ready => None,
+                    DataStatus::Failed(err) => rsx! {
+                        div {
+                            class: "mb-6 text-sm text-red-600",
+                            "Couldn't load the dictionary: {err}"
+                        }
+                    },
+                }}
+                
+                if show_radicals() && is_installed() {
+                    RadicalPicker {
+                        on_pick: move |kanji: char| {
+                            // Picked kanji go straight into the search
+                            go_to_search(format!("{}{}", state.query.read(), kanji));
+                        }
                     }
                 }
+                
+                Outlet::<Route> {}
+            }
+            
+            // Which JMdict build this is, so bug reports can say
+            footer {
+                class: "max-w-4xl mx-auto px-4 py-6 text-xs text-gray-400 border-t",
+                "Data: {dictionary_info()}"
             }
         }
     }
 }
 
+/// `/`: nothing searched yet
+#[component]
+fn Home() -> Element {
+    let state = use_context::<AppState>();
+    use_effect(move || state.search(String::new()));
+    None
+}
+
+/// `/search?q=…`
+#[component]
+fn Search(query: SearchQuery) -> Element {
+    rsx! {
+        // Keyed by the query, so every new URL (typing, back/forward, a shared
+        // link) mounts a fresh `SearchPage`, which runs its search
+        SearchPage {
+            key: "{query.q}",
+            q: query.q.clone()
+        }
+    }
+}
+
+#[component]
+fn SearchPage(q: String) -> Element {
+    let state = use_context::<AppState>();
+    use_effect(move || state.search(q.clone()));
+    
+    rsx! {
+        if !state.results.read().is_empty() {
+            ResultsSection {
+                results: state.results.read().clone(),
+                query: state.query.read().clone(),
+                lang: state.lang.read().clone()
+            }
+        }
+        
+        if !state.names.read().is_empty() {
+            NamesSection {
+                names: state.names.read().clone()
+            }
+        }
+    }
+}
+
+/// `/entry/<jmdict-id>`: one word with every sense
+#[component]
+fn Entry(id: String) -> Element {
+    let state = use_context::<AppState>();
+    // Subscribes to the status, so the entry shows once the data has loaded
+    if !state.status.read().is_loaded() {
+        return None;
+    }
+    
+    match get_by_jmdict_id(&id) {
+        Ok(entry) => rsx! {
+            EntryDetail {
+                entry: entry,
+                lang: state.lang.read().clone(),
+                query: state.query.read().clone()
+            }
+        },
+        Err(err) => rsx! {
+            div {
+                class: "text-gray-600",
+                "{err}"
+            }
+        },
+    }
+}
+
 #[component]
 fn SearchBox(query: String, on_search: EventHandler<String>) -> Element {
     rsx! {
@@ -95,10 +436,27 @@ fn SearchBox(query: String, on_search: EventHandler<String>) -> Element {
     }
 }
 
+/// Display name for a JMdict gloss language code
+fn language_name(code: &str) -> &str {
+    match code {
+        "eng" => "English",
+        "ger" => "Deutsch",
+        "fre" => "Français",
+        "rus" => "Русский",
+        "spa" => "Español",
+        "dut" => "Nederlands",
+        "hun" => "Magyar",
+        "swe" => "Svenska",
+        "slv" => "Slovenščina",
+        _ => code,
+    }
+}
+
 #[component]
 fn ResultsSection(
     results: Vec<WordEntry>, 
-    query: String
+    query: String,
+    lang: String
 ) -> Element {
     rsx! {
         div {
@@ -123,7 +481,8 @@ fn ResultsSection(
                         ResultCard {
                             key: "{i}",
                             entry: entry.clone(),
-                            rank: i + 1
+                            rank: i + 1,
+                            lang: lang.clone()
                         }
                     }
                 })}
@@ -140,7 +499,12 @@ fn ResultsSection(
 }
 
 #[component]
-fn ResultCard(entry: WordEntry, rank: usize) -> Element {
+fn ResultCard(entry: WordEntry, rank: usize, lang: String) -> Element {
+    let mut selected_kanji = use_signal(|| None::<char>);
+    let glosses = entry.glosses_in(&lang);
+    let kanji_info = selected_kanji().and_then(get_kanji);
+    let examples = examples_for(entry.id);
+    
     rsx! {
         div {
             class: "bg-white rounded-lg shadow-sm border border-gray-200 p-6 hover:shadow-md transition-shadow",
@@ -156,74 +520,508 @@ fn ResultCard(entry: WordEntry, rank: usize) -> Element {
                 div {
                     class: "flex-1 min-w-0",
                     
+                    Headword {
+                        entry: entry.clone(),
+                        selected_kanji: selected_kanji
+                    }
+                    
+                    Accents {
+                        entry: entry.clone()
+                    }
+                    
+                    // Definitions in the chosen language
+                    if !glosses.is_empty() {
+                        div {
+                            class: "text-gray-700 mb-2",
+{glosses.iter().take(3).enumerate().map(|(i, gloss)| rsx! {
+                                span {
+                                    "{gloss}"
+                                    if i < glosses.len().min(3) - 1 { "; " }
+                                }
+                            })}
+                            Link {
+                                class: "ml-2 text-sm text-blue-600 hover:text-blue-800",
+                                to: Route::Entry { id: entry.id.to_string() },
+                                "All senses →"
+                            }
+                        }
+                    }
+                    
+                    PartsOfSpeech {
+                        pos: entry.pos.clone()
+                    }
+                    
+                    Examples {
+                        examples: examples.into_iter().take(3).collect::<Vec<_>>()
+                    }
+                    
+{kanji_info.map(|info| rsx! {
+                        KanjiPanel {
+                            info: info,
+                            on_close: move |_| selected_kanji.set(None)
+                        }
+                    })}
+                }
+            }
+        }
+    }
+}
+
+/// Entry page body: every reading, sense and example of one word
+#[component]
+fn EntryDetail(entry: WordEntry, lang: String, query: String) -> Element {
+    let mut selected_kanji = use_signal(|| None::<char>);
+    let senses = entry.senses_in(&lang);
+    let kanji_info = selected_kanji().and_then(get_kanji);
+    let examples = examples_for(entry.id);
+    let references: Vec<(XrefKind, WordEntry)> = cross_references(entry.id)
+        .into_iter()
+        .map(|xref| (xref.kind, get_word_entry(xref.index)))
+        .collect();
+    
+    rsx! {
+        div {
+            class: "space-y-4",
+            
+            // Back to the search this entry was opened from
+            if !query.trim().is_empty() {
+                Link {
+                    class: "text-sm text-blue-600 hover:text-blue-800",
+                    to: search_route(query.clone()),
+                    "← Results for \"{query}\""
+                }
+            }
+            
+            div {
+                class: "bg-white rounded-lg shadow-sm border border-gray-200 p-6",
+                
+                Headword {
+                    entry: entry.clone(),
+                    selected_kanji: selected_kanji
+                }
+                
+                Accents {
+                    entry: entry.clone()
+                }
+                
+                PartsOfSpeech {
+                    pos: entry.pos.clone()
+                }
+                
+                // Every sense, numbered as in JMdict
+                if !senses.is_empty() {
+                    ol {
+                        class: "mt-4 space-y-2 list-decimal list-inside text-gray-700",
+{senses.iter().map(|glosses| {
+                            let text = glosses.join("; ");
+                            rsx! {
+                                li {
+                                    "{text}"
+                                }
+                            }
+                        })}
+                    }
+                }
+                
+                // Related words and antonyms, each linking to its entry
+                if !references.is_empty() {
                     div {
-                        class: "flex flex-wrap items-center gap-2 mb-2",
-                        
-                        // Kanji
-                        if !entry.kanji.is_empty() {
-                            div {
-                                class: "flex flex-wrap gap-1",
+                        class: "mt-4 flex flex-wrap items-center gap-2 text-sm",
+                        span {
+                            class: "text-gray-500",
+                            "See also:"
+                        }
+{references.iter().map(|(kind, target)| {
+                            let headword = target.kanji.first().or(target.kana.first()).copied().unwrap_or_default();
+                            let label = if *kind == XrefKind::Antonym { " (antonym)" } else { "" };
+                            rsx! {
+                                Link {
+                                    class: "text-blue-600 hover:text-blue-800",
+                                    to: Route::Entry { id: target.id.to_string() },
+                                    "{headword}{label}"
+                                }
+                            }
+                        })}
+                    }
+                }
+                
+                Examples {
+                    examples: examples
+                }
+                
+{kanji_info.map(|info| rsx! {
+                    KanjiPanel {
+                        info: info,
+                        on_close: move |_| selected_kanji.set(None)
+                    }
+                })}
+                
+                p {
+                    class: "mt-4 text-xs text-gray-400",
+                    "JMdict {entry.id}"
+                }
+            }
+        }
+    }
+}
+
+/// Kanji forms (each kanji opens its KANJIDIC2 panel), readings and the common marker
+#[component]
+fn Headword(entry: WordEntry, selected_kanji: Signal<Option<char>>) -> Element {
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-2 mb-2",
+            
+            // Kanji
+            if !entry.kanji.is_empty() {
+                div {
+                    class: "flex flex-wrap gap-1",
 {entry.kanji.iter().map(|kanji| rsx! {
+                        span {
+                            class: "text-2xl font-bold text-purple-600",
+                            // Each kanji opens its KANJIDIC2 panel when clicked
+{kanji.chars().map(|c| {
+                                let known = get_kanji(c).is_some();
+                                rsx! {
                                     span {
-                                        class: "text-2xl font-bold text-purple-600",
-                                        "{kanji}"
+                                        class: if known { "cursor-pointer hover:text-purple-800 hover:underline" } else { "" },
+                                        onclick: move |_| {
+                                            if known {
+                                                selected_kanji.set(if selected_kanji() == Some(c) { None } else { Some(c) });
+                                            }
+                                        },
+                                        "{c}"
                                     }
-                                })}
-                            }
+                                }
+                            })}
                         }
-                        
-                        // Kana
-                        if !entry.kana.is_empty() {
-                            div {
-                                class: "flex flex-wrap gap-1",
-                                "("
+                    })}
+                }
+            }
+            
+            // Kana
+            if !entry.kana.is_empty() {
+                div {
+                    class: "flex flex-wrap gap-1",
+                    "("
 {entry.kana.iter().enumerate().map(|(i, kana)| rsx! {
-                                    span {
-                                        class: "text-lg text-blue-600",
-                                        "{kana}"
-                                        if i < entry.kana.len() - 1 { ", " }
-                                    }
-                                })}
-                                ")"
-                            }
-                        }
-                        
-                        // Common word indicator
-                        if entry.is_common {
-                            span {
-                                class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-yellow-100 text-yellow-800 rounded-full",
-                                "⭐ Common"
-                            }
+                        span {
+                            class: "text-lg text-blue-600",
+                            "{kana}"
+                            if i < entry.kana.len() - 1 { ", " }
                         }
+                    })}
+                    ")"
+                }
+            }
+            
+            // Common word indicator
+            if entry.is_common {
+                span {
+                    class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-yellow-100 text-yellow-800 rounded-full",
+                    "⭐ Common"
+                }
+            }
+        }
+    }
+}
+
+/// Pitch accent contours, one per reading and accent
+#[component]
+fn Accents(entry: WordEntry) -> Element {
+    rsx! {
+        if entry.accents.iter().any(|accents| !accents.is_empty()) {
+            div {
+                class: "flex flex-wrap gap-3 mb-2",
+{entry.kana.iter().zip(&entry.accents).flat_map(|(kana, accents)| accents.iter().map(move |&accent| rsx! {
+                    PitchContour {
+                        kana: kana.to_string(),
+                        accent: accent
                     }
-                    
-                    // English definitions
-                    if !entry.english.is_empty() {
+                }))}
+            }
+        }
+    }
+}
+
+#[component]
+fn PartsOfSpeech(pos: Vec<&'static str>) -> Element {
+    rsx! {
+        if !pos.is_empty() {
+            div {
+                class: "flex flex-wrap gap-1",
+{pos.iter().map(|pos| rsx! {
+                    span {
+                        class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-gray-100 text-gray-600 rounded",
+                        "{pos}"
+                    }
+                })}
+            }
+        }
+    }
+}
+
+/// Example sentences with the headword highlighted
+#[component]
+fn Examples(examples: Vec<ExampleSentence>) -> Element {
+    rsx! {
+        if !examples.is_empty() {
+            div {
+                class: "mt-3 space-y-2 border-l-2 border-gray-200 pl-3",
+{examples.iter().map(|example| {
+                    let (before, headword, after) = example.split_highlight();
+                    rsx! {
                         div {
-                            class: "text-gray-700 mb-2",
-{entry.english.iter().take(3).enumerate().map(|(i, eng)| rsx! {
-                                span {
-                                    "{eng}"
-                                    if i < entry.english.len().min(3) - 1 { "; " }
+                            class: "text-sm",
+                            p {
+                                class: "text-gray-800",
+                                "{before}"
+                                mark {
+                                    class: "bg-yellow-100 text-purple-700 rounded px-0.5",
+                                    "{headword}"
                                 }
-                            })}
+                                "{after}"
+                            }
+                            p {
+                                class: "text-gray-500",
+                                "{example.english}"
+                            }
                         }
                     }
-                    
-                    // Part of speech
-                    if !entry.pos.is_empty() {
-                        div {
-                            class: "flex flex-wrap gap-1",
-{entry.pos.iter().map(|pos| rsx! {
-                                span {
-                                    class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-gray-100 text-gray-600 rounded",
-                                    "{pos}"
+                })}
+            }
+        }
+    }
+}
+
+#[component]
+fn NamesSection(names: Vec<NameEntry>) -> Element {
+    rsx! {
+        div {
+            class: "mt-10 space-y-3",
+            
+            h2 {
+                class: "text-lg font-semibold text-gray-700",
+                "Names"
+            }
+            
+{names.iter().enumerate().map(|(i, entry)| {
+                let forms = if entry.kanji.is_empty() { entry.kana.join(", ") } else { entry.kanji.join(", ") };
+                let readings = if entry.kanji.is_empty() { String::new() } else { entry.kana.join(", ") };
+                let translations = entry.translations.join("; ");
+                rsx! {
+                    div {
+                        key: "{i}",
+                        class: "bg-white rounded-lg border border-gray-200 px-4 py-3 flex flex-wrap items-center gap-2",
+                        span { class: "text-xl font-bold text-gray-800", "{forms}" }
+                        if !readings.is_empty() {
+                            span { class: "text-blue-600", "({readings})" }
+                        }
+                        span { class: "text-gray-700", "{translations}" }
+{entry.name_types.iter().map(|name_type| rsx! {
+                            span {
+                                class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-indigo-50 text-indigo-700 rounded",
+                                "{name_type}"
+                            }
+                        })}
+                    }
+                }
+            })}
+        }
+    }
+}
+
+#[component]
+fn RadicalPicker(on_pick: EventHandler<char>) -> Element {
+    let mut selected = use_signal(Vec::<char>::new);
+    let all_radicals = use_hook(radicals);
+    let candidates = kanji_by_components(&selected.read());
+    let compatible = compatible_radicals(&selected.read());
+    
+    rsx! {
+        div {
+            class: "mb-8 p-4 bg-white border border-gray-200 rounded-lg shadow-sm",
+            
+            // Radical grid, grouped by stroke count
+            div {
+                class: "flex flex-wrap gap-1",
+{all_radicals.iter().enumerate().map(|(i, radical)| {
+                    let literal = radical.literal;
+                    let new_group = i == 0 || all_radicals[i - 1].stroke_count != radical.stroke_count;
+                    let is_selected = selected.read().contains(&literal);
+                    let class = if is_selected {
+                        "w-8 h-8 rounded bg-purple-600 text-white"
+                    } else if compatible.contains(&literal) {
+                        "w-8 h-8 rounded bg-gray-100 text-gray-900 hover:bg-purple-100"
+                    } else {
+                        "w-8 h-8 rounded bg-gray-50 text-gray-300 cursor-not-allowed"
+                    };
+                    rsx! {
+                        if new_group {
+                            span {
+                                class: "w-8 h-8 rounded bg-gray-700 text-white text-xs flex items-center justify-center",
+                                "{radical.stroke_count}"
+                            }
+                        }
+                        button {
+                            class: "{class}",
+                            disabled: !is_selected && !compatible.contains(&literal),
+                            onclick: move |_| {
+                                let mut selected = selected.write();
+                                match selected.iter().position(|&r| r == literal) {
+                                    Some(i) => { selected.remove(i); }
+                                    None => selected.push(literal),
                                 }
-                            })}
+                            },
+                            "{literal}"
                         }
                     }
+                })}
+            }
+            
+            // Matching kanji, fewest strokes first
+            if !selected.read().is_empty() {
+                div {
+                    class: "mt-4 pt-4 border-t border-gray-200",
+                    div {
+                        class: "text-sm text-gray-500 mb-2",
+                        "{candidates.len()} matching kanji"
+                    }
+                    div {
+                        class: "flex flex-wrap gap-1",
+{candidates.iter().map(|&kanji| rsx! {
+                            button {
+                                class: "w-10 h-10 text-2xl rounded text-purple-700 hover:bg-purple-100",
+                                onclick: move |_| on_pick.call(kanji),
+                                "{kanji}"
+                            }
+                        })}
+                    }
                 }
             }
         }
     }
+}
+
+#[component]
+fn KanjiPanel(info: KanjiInfo, on_close: EventHandler<()>) -> Element {
+    let radical = match info.radical_char() {
+        Some(c) => format!("{} ({})", c, info.radical),
+        None => "-".to_string(),
+    };
+    let details = [
+        ("Strokes", info.stroke_count.to_string()),
+        ("Radical", radical),
+        ("Grade", info.grade.map_or("-".to_string(), |g| g.to_string())),
+        ("JLPT", info.jlpt.map_or("-".to_string(), |j| j.to_string())),
+        ("Frequency", info.frequency.map_or("-".to_string(), |f| format!("#{}", f))),
+    ];
+    let on_readings = info.on_readings.join("、");
+    let kun_readings = info.kun_readings.join("、");
+    let meanings = info.meanings.join("; ");
+    let parts: String = components_of(info.literal).iter().collect();
+    
+    rsx! {
+        div {
+            class: "mt-4 p-4 bg-purple-50 border border-purple-200 rounded-lg",
+            
+            div {
+                class: "flex items-start justify-between",
+                span {
+                    class: "text-5xl font-bold text-purple-700",
+                    "{info.literal}"
+                }
+                button {
+                    class: "text-gray-400 hover:text-gray-600",
+                    onclick: move |_| on_close.call(()),
+                    "✕"
+                }
+            }
+            
+            div {
+                class: "mt-3 space-y-1 text-gray-700",
+                div { span { class: "text-gray-500", "On: " } "{on_readings}" }
+                div { span { class: "text-gray-500", "Kun: " } "{kun_readings}" }
+                div { span { class: "text-gray-500", "Meanings: " } "{meanings}" }
+                if !parts.is_empty() {
+                    div { span { class: "text-gray-500", "Parts: " } "{parts}" }
+                }
+            }
+            
+            div {
+                class: "mt-3 flex flex-wrap gap-2",
+{details.iter().map(|(label, value)| rsx! {
+                    span {
+                        class: "inline-flex items-center px-2 py-1 text-xs font-medium bg-white text-gray-600 rounded border border-purple-100",
+                        "{label}: {value}"
+                    }
+                })}
+            }
+        }
+    }
+}
+
+#[component]
+fn PitchContour(kana: String, accent: u8) -> Element {
+    const STEP: usize = 22;
+    let morae = pitch::morae(&kana);
+    let pattern = pitch::pitch_pattern(morae.len(), accent);
+    let width = STEP * pattern.len();
+    
+    // High morae sit on the top line, low morae on the bottom line
+    let point = |i: usize, high: bool| (i * STEP + STEP / 2, if high { 8 } else { 24 });
+    let points = pattern
+        .iter()
+        .enumerate()
+        .map(|(i, &high)| {
+            let (x, y) = point(i, high);
+            format!("{x},{y}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    
+    rsx! {
+        svg {
+            class: "inline-block align-middle",
+            width: "{width}",
+            height: "48",
+            view_box: "0 0 {width} 48",
+            
+            polyline {
+                points: "{points}",
+                fill: "none",
+                stroke: "#2563eb",
+                stroke_width: "1.5"
+            }
+            
+            // The trailing hollow dot is the particle that follows the word
+{pattern.iter().enumerate().map(|(i, &high)| {
+                let (x, y) = point(i, high);
+                rsx! {
+                    circle {
+                        cx: "{x}",
+                        cy: "{y}",
+                        r: "3.5",
+                        fill: if i < morae.len() { "#2563eb" } else { "white" },
+                        stroke: "#2563eb",
+                        stroke_width: "1.5"
+                    }
+                }
+            })}
+            
+{morae.iter().enumerate().map(|(i, mora)| {
+                let (x, _) = point(i, false);
+                rsx! {
+                    text {
+                        x: "{x}",
+                        y: "42",
+                        text_anchor: "middle",
+                        font_size: "13",
+                        fill: "#374151",
+                        "{mora}"
+                    }
+                }
+            })}
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+//! Offline support: registers `public/sw.js`, which precaches the app shell
+//! and the dictionary file so the installed app works without a network
+
+use crate::data::CACHE_NAME;
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+/// Service worker script, relative to the page. The cache name goes in the
+/// query, so the worker fills the cache the dictionary loader reads and a new
+/// dictionary file (a new cache name) installs a new worker.
+fn service_worker_url() -> String {
+    format!("./sw.js?cache={}", CACHE_NAME)
+}
+
+pub fn register_service_worker() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    // Service workers only exist in secure contexts (https or localhost)
+    let navigator = window.navigator();
+    if !Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false) {
+        log::info!("Service workers are unavailable; the app won't work offline");
+        return;
+    }
+    let registration = navigator.service_worker().register(&service_worker_url());
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err) = JsFuture::from(registration).await {
+            log::warn!("Failed to register the service worker: {:?}", err);
+        }
+    });
+}
@@ -0,0 +1,169 @@
+//! Dictionary search in a dedicated Web Worker
+//!
+//! Building the search indices and scoring candidates would otherwise block
+//! the page. The worker loads this same wasm module (see `public/worker.js`),
+//! and `main` calls `serve` instead of launching the UI when it finds itself in
+//! a worker. Both sides load the same dictionary file (see `data`), so messages
+//! only carry the query and the indices of the matching entries.
+//!
+//! That is two copies of the dictionary, one in each wasm instance's memory.
+//! The worker only searches: the page reads entries, kanji, radicals, examples
+//! and cross-references while rendering, and doing those lookups through
+//! messages would make every view asynchronous. Sharing one copy needs a
+//! `SharedArrayBuffer`, which browsers only allow on cross-origin isolated
+//! pages, and static hosts can't be relied on to send those headers.
+
+use crate::data::load_dictionary;
+use japandict_core::{build_search_indices_with_progress, default_language, search_indices_in, search_name_indices};
+use js_sys::{Object, Reflect, Uint32Array};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker, WorkerOptions, WorkerType};
+
+/// Worker script, relative to the page
+const WORKER_SCRIPT: &str = "./worker.js";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum WorkerEvent {
+    /// `done` of `total` search indices are built
+    Progress { done: usize, total: usize },
+    /// Indices are built; queued requests are answered from here on
+    Ready,
+    /// The worker couldn't load the dictionary
+    Failed(String),
+    /// Entry indices for the request with this id
+    Results { id: u32, words: Vec<usize>, names: Vec<usize> },
+}
+
+pub fn is_worker() -> bool {
+    js_sys::global().has_type::<DedicatedWorkerGlobalScope>()
+}
+
+/// Worker side: load the dictionary, build the indices, then answer search requests
+pub fn serve() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+
+    // Requests that arrive while the data loads are held here (`None` once ready)
+    let pending = Rc::new(RefCell::new(Some(Vec::new())));
+
+    let reply_scope = scope.clone();
+    let queue = pending.clone();
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        match queue.borrow_mut().as_mut() {
+            Some(requests) => requests.push(event.data()),
+            None => answer(&reply_scope, &event.data()),
+        }
+    });
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(err) = load_dictionary().await.and_then(|dictionary| dictionary.install().map_err(|err| err.to_string())) {
+            post(&scope, &[("kind", "failed".into()), ("error", err.into())]);
+            return;
+        }
+        build_search_indices_with_progress(|done, total| {
+            post(&scope, &[("kind", "progress".into()), ("done", (done as u32).into()), ("total", (total as u32).into())]);
+        });
+        post(&scope, &[("kind", "ready".into())]);
+
+        // Only the latest search is still wanted; the page drops older answers
+        let requests = pending.borrow_mut().take().unwrap_or_default();
+        if let Some(request) = requests.last() {
+            answer(&scope, request);
+        }
+    });
+}
+
+fn answer(scope: &DedicatedWorkerGlobalScope, request: &JsValue) {
+    let query = field(request, "query").as_string().unwrap_or_default();
+    let lang = field(request, "lang").as_string().unwrap_or_else(|| default_language().to_string());
+    let names = if field(request, "names").is_truthy() { search_name_indices(&query) } else { Vec::new() };
+    let words = search_indices_in(&query, &lang);
+
+    post(scope, &[
+        ("kind", "results".into()),
+        ("id", field(request, "id")),
+        ("words", indices_array(&words)),
+        ("names", indices_array(&names)),
+    ]);
+}
+
+/// Page side: handle to the search worker
+pub struct SearchWorker {
+    worker: Worker,
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+}
+
+impl SearchWorker {
+    pub fn spawn(on_event: impl Fn(WorkerEvent) + 'static) -> Self {
+        let options = WorkerOptions::new();
+        options.set_type(WorkerType::Module);
+        let worker = Worker::new_with_options(WORKER_SCRIPT, &options).expect("Failed to start the search worker");
+
+        let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            match parse_event(&event.data()) {
+                Some(event) => on_event(event),
+                None => log::warn!("Unexpected message from the search worker"),
+            }
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        SearchWorker { worker, _onmessage: onmessage }
+    }
+
+    /// Queue a search; the answer arrives as `WorkerEvent::Results` with the same id
+    pub fn search(&self, id: u32, query: &str, lang: &str, names: bool) {
+        let request = object(&[("id", id.into()), ("query", query.into()), ("lang", lang.into()), ("names", names.into())]);
+        if let Err(err) = self.worker.post_message(&request) {
+            log::error!("Failed to send a search to the worker: {:?}", err);
+        }
+    }
+}
+
+fn parse_event(message: &JsValue) -> Option<WorkerEvent> {
+    let number = |key| field(message, key).as_f64().map(|n| n as usize);
+    match field(message, "kind").as_string()?.as_str() {
+        "progress" => Some(WorkerEvent::Progress { done: number("done")?, total: number("total")? }),
+        "ready" => Some(WorkerEvent::Ready),
+        "failed" => Some(WorkerEvent::Failed(field(message, "error").as_string().unwrap_or_default())),
+        "results" => Some(WorkerEvent::Results {
+            id: number("id")? as u32,
+            words: indices_vec(&field(message, "words")),
+            names: indices_vec(&field(message, "names")),
+        }),
+        _ => None,
+    }
+}
+
+fn post(scope: &DedicatedWorkerGlobalScope, fields: &[(&str, JsValue)]) {
+    if let Err(err) = scope.post_message(&object(fields)) {
+        log::error!("Failed to post from the search worker: {:?}", err);
+    }
+}
+
+fn object(fields: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in fields {
+        Reflect::set(&object, &JsValue::from_str(key), value).expect("Failed to set a message field");
+    }
+    object.into()
+}
+
+fn field(message: &JsValue, key: &str) -> JsValue {
+    Reflect::get(message, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED)
+}
+
+fn indices_array(indices: &[usize]) -> JsValue {
+    let indices: Vec<u32> = indices.iter().map(|&idx| idx as u32).collect();
+    Uint32Array::from(indices.as_slice()).into()
+}
+
+fn indices_vec(value: &JsValue) -> Vec<usize> {
+    match value.dyn_ref::<Uint32Array>() {
+        Some(array) => array.to_vec().into_iter().map(|idx| idx as usize).collect(),
+        None => Vec::new(),
+    }
+}
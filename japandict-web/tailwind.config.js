@@ -0,0 +1,10 @@
+// Tailwind is compiled into public/tailwind.css (`make web-css`) rather than
+// loaded from the CDN, so the installed app works offline
+/** @type {import('tailwindcss').Config} */
+module.exports = {
+  content: ["./src/**/*.rs", "./index.html"],
+  theme: {
+    extend: {},
+  },
+  plugins: [],
+};
@@ -0,0 +1,92 @@
+//! Pitch accent data in Kanjium's `accents.txt` layout
+//!
+//! Each line is `kanji<TAB>reading<TAB>accent`, where accent is a comma
+//! separated list of downstep positions (`0` for heiban). Kana-only words
+//! repeat the reading in the kanji column. Some rows annotate positions with
+//! a part of speech, e.g. `(名)0,(副)3` - those annotations are dropped.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub struct AccentTable {
+    accents: HashMap<(String, String), Vec<u8>>,
+}
+
+impl AccentTable {
+    pub fn load(path: &Path) -> std::io::Result<AccentTable> {
+        let content = fs::read_to_string(path)?;
+        let mut accents = HashMap::new();
+
+        for line in content.lines() {
+            let mut fields = line.split('\t');
+            let (Some(written), Some(reading), Some(accent)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+
+            let positions = parse_positions(accent);
+            if !positions.is_empty() {
+                accents.insert((written.to_string(), reading.to_string()), positions);
+            }
+        }
+
+        Ok(AccentTable { accents })
+    }
+
+    pub fn len(&self) -> usize {
+        self.accents.len()
+    }
+
+    /// Find the accent positions for a reading, trying each written form
+    /// first and falling back to the reading itself for kana-only words.
+    pub fn lookup<'a>(&self, written_forms: impl IntoIterator<Item = &'a str>, reading: &'a str) -> Option<&[u8]> {
+        written_forms
+            .into_iter()
+            .chain(std::iter::once(reading))
+            .find_map(|written| self.accents.get(&(written.to_string(), reading.to_string())))
+            .map(|positions| positions.as_slice())
+    }
+}
+
+fn parse_positions(field: &str) -> Vec<u8> {
+    let mut positions = Vec::new();
+    let mut current: Option<u32> = None;
+
+    for c in field.chars().chain(std::iter::once(',')) {
+        if let Some(digit) = c.to_digit(10) {
+            current = Some(current.unwrap_or(0) * 10 + digit);
+        } else if let Some(value) = current.take() {
+            if let Ok(position) = u8::try_from(value) {
+                if !positions.contains(&position) {
+                    positions.push(position);
+                }
+            }
+        }
+    }
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_downstep_positions() {
+        assert_eq!(parse_positions("0"), vec![0]);
+        assert_eq!(parse_positions("1,0"), vec![1, 0]);
+        assert_eq!(parse_positions("12"), vec![12]);
+    }
+
+    #[test]
+    fn drops_part_of_speech_annotations_and_duplicates() {
+        assert_eq!(parse_positions("(名)0,(副)3"), vec![0, 3]);
+        assert_eq!(parse_positions("(名)0,(副)0"), vec![0]);
+    }
+
+    #[test]
+    fn skips_positions_out_of_range() {
+        assert_eq!(parse_positions("300,2"), vec![2]);
+        assert!(parse_positions("").is_empty());
+    }
+}
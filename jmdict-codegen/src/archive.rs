@@ -0,0 +1,50 @@
+//! Reading jmdict-simplified JSON documents, either as plain `.json` files,
+//! gzipped (`.json.gz`), or wrapped in the `.json.tgz` / `.json.zip` archives
+//! the project publishes.
+
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use tar::Archive;
+use zip::ZipArchive;
+
+/// Call `f` with a reader over the JSON document in a `.json`, `.json.gz`,
+/// `.tgz`/`.tar.gz` or `.zip` file. Archives are decompressed on the fly, so
+/// the document is never held in memory as a whole.
+pub fn with_json_reader<T>(path: &Path, f: impl FnOnce(&mut dyn Read) -> T) -> T {
+    let name = path.to_str().unwrap_or_default();
+    let mut file = fs::File::open(path).expect("Failed to open input file");
+
+    if name.ends_with(".tgz") || name.ends_with(".tar.gz") {
+        // First `.json` entry of the tarball
+        let mut archive = Archive::new(GzDecoder::new(file));
+        for entry_result in archive.entries().expect("Failed to read tar entries") {
+            let mut entry = entry_result.expect("Failed to read tar entry");
+            let is_json = entry.path().expect("Failed to read entry path").to_str().is_some_and(|p| p.ends_with(".json"));
+            if is_json {
+                return f(&mut entry);
+            }
+        }
+        panic!("No .json file in {}", path.display());
+    } else if name.ends_with(".zip") {
+        // First `.json` entry of the zip archive
+        let mut archive = ZipArchive::new(file).expect("Failed to read zip archive");
+        let index = (0..archive.len())
+            .find(|&i| archive.by_index(i).is_ok_and(|entry| entry.name().ends_with(".json")))
+            .unwrap_or_else(|| panic!("No .json file in {}", path.display()));
+        let mut entry = archive.by_index(index).expect("Failed to read zip entry");
+        f(&mut entry)
+    } else if name.ends_with(".gz") {
+        f(&mut GzDecoder::new(file))
+    } else {
+        f(&mut file)
+    }
+}
+
+/// Deserialize a JSON document from any file `with_json_reader` accepts,
+/// straight from the (decompressing) reader.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> serde_json::Result<T> {
+    with_json_reader(path, |reader| serde_json::from_reader(BufReader::new(reader)))
+}
@@ -0,0 +1,47 @@
+//! Per-form and per-sense details of word entries
+//!
+//! The packed entries only keep what search and result lists need: forms,
+//! glosses and the first sense's parts of speech. Entry detail views also show
+//! each form's tags and priority markers, and each sense's own parts of speech
+//! and notes, which are packed here into a separate u32 table:
+//! - per kanji form, then per kana form: common (0/1), tag count, priority
+//!   count, then the tag and priority string indices
+//! - per sense with glosses in the selected languages: part of speech, misc,
+//!   field, dialect and info counts, then their string indices
+//!
+//! Strings go into the main JMdict string pool.
+
+use crate::jmdict::{Sense, Word};
+use crate::pool::{checked_count, StringPool};
+
+/// Append the details of `word` to `details`, numbering senses the way the
+/// entry's gloss senses are numbered
+pub fn pack(word: &Word, languages: &[String], pool: &mut StringPool, details: &mut Vec<u32>) {
+    let kanji_forms = word.kanji.iter().flatten().map(|k| (k.common, &k.tags, &k.priority));
+    let kana_forms = word.kana.iter().map(|k| (k.common, &k.tags, &k.priority));
+    for (common, tags, priority) in kanji_forms.chain(kana_forms) {
+        let tags = tags.as_deref().unwrap_or_default();
+        let priority = priority.as_deref().unwrap_or_default();
+        details.push(common.unwrap_or(false) as u32);
+        details.push(checked_count(tags.len(), "form tags", &word.id));
+        details.push(checked_count(priority.len(), "form priorities", &word.id));
+        details.extend(tags.iter().chain(priority).map(|s| pool.get_or_insert(s)));
+    }
+
+    let has_glosses = |sense: &&Sense| sense.gloss.iter().any(|gloss| languages.contains(&gloss.lang));
+    for sense in word.sense.iter().filter(has_glosses) {
+        let lists = [
+            ("parts of speech", &sense.part_of_speech),
+            ("misc tags", &sense.misc),
+            ("field tags", &sense.field),
+            ("dialect tags", &sense.dialect),
+            ("sense notes", &sense.info),
+        ];
+        for (what, list) in lists {
+            details.push(checked_count(list.as_ref().map_or(0, Vec::len), what, &word.id));
+        }
+        for (_, list) in lists {
+            details.extend(list.iter().flatten().map(|s| pool.get_or_insert(s)));
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Tatoeba example sentences linked to JMdict entries
+//!
+//! Two local Tatoeba exports are combined:
+//! - the Japanese-English sentence pairs TSV (`jpn_id, jpn_text, eng_id, eng_text`)
+//! - the Japanese indices (`jpn_indices.csv`: `sentence_id, meaning_id, index`),
+//!   where the index lists the headwords used in the sentence as
+//!   `headword(reading)[sense]{surface form}~`, with `~` marking checked examples
+
+use crate::pool::StringPool;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+const MAX_EXAMPLES_PER_ENTRY: usize = 5;
+
+/// Packed example tables. Sentences are `(tatoeba_id, japanese, english)`
+/// triples and links are `(sentence, sense, highlight)` triples, grouped per
+/// entry id: entry `entry_ids[i]` owns `links[link_offsets[i]..link_offsets[i + 1]]`
+/// (in triples).
+#[derive(Default)]
+pub struct ExampleTable {
+    pub strings: Vec<u8>,
+    pub string_offsets: Vec<u32>,
+    pub sentences: Vec<u32>,
+    pub entry_ids: Vec<u32>,
+    pub link_offsets: Vec<u32>,
+    pub links: Vec<u32>,
+}
+
+struct IndexToken<'a> {
+    headword: &'a str,
+    reading: Option<&'a str>,
+    sense: Option<u32>,
+    surface: Option<&'a str>,
+    verified: bool,
+}
+
+fn delimited(token: &str, open: char, close: char) -> Option<&str> {
+    let start = token.find(open)? + open.len_utf8();
+    let end = start + token[start..].find(close)?;
+    Some(&token[start..end])
+}
+
+fn parse_token(token: &str) -> Option<IndexToken<'_>> {
+    let verified = token.ends_with('~');
+    let headword_end = token.find(['(', '[', '{', '~']).unwrap_or(token.len());
+    let headword = &token[..headword_end];
+    if headword.is_empty() {
+        return None;
+    }
+
+    Some(IndexToken {
+        headword,
+        reading: delimited(token, '(', ')'),
+        sense: delimited(token, '[', ']').and_then(|s| s.parse().ok()),
+        surface: delimited(token, '{', '}'),
+        verified,
+    })
+}
+
+/// Link sentences to entries. `resolve` maps a headword and optional reading
+/// to a JMdict entry id.
+pub fn pack(
+    pairs_path: &Path,
+    index_path: &Path,
+    resolve: impl Fn(&str, Option<&str>) -> Option<u32>,
+) -> std::io::Result<ExampleTable> {
+    // First English translation of each Japanese sentence
+    let pairs_content = fs::read_to_string(pairs_path)?;
+    let mut pairs: HashMap<u32, (&str, &str)> = HashMap::new();
+    for line in pairs_content.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if let [jpn_id, jpn_text, _eng_id, eng_text, ..] = fields[..] {
+            if let Ok(jpn_id) = jpn_id.parse() {
+                pairs.entry(jpn_id).or_insert((jpn_text, eng_text));
+            }
+        }
+    }
+
+    // Candidate links per entry id: (verified, sentence id, sense, highlight)
+    let index_content = fs::read_to_string(index_path)?;
+    let mut candidates: BTreeMap<u32, Vec<(bool, u32, u32, &str)>> = BTreeMap::new();
+    for line in index_content.lines() {
+        let mut fields = line.split('\t');
+        let (Some(sentence_id), Some(_meaning_id), Some(index)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        let Ok(sentence_id) = sentence_id.parse::<u32>() else {
+            continue;
+        };
+        if !pairs.contains_key(&sentence_id) {
+            continue;
+        }
+
+        for token in index.split_whitespace().filter_map(parse_token) {
+            if let Some(entry_id) = resolve(token.headword, token.reading) {
+                let links = candidates.entry(entry_id).or_default();
+                if !links.iter().any(|link| link.1 == sentence_id) {
+                    links.push((token.verified, sentence_id, token.sense.unwrap_or(0), token.surface.unwrap_or(token.headword)));
+                }
+            }
+        }
+    }
+
+    let mut table = ExampleTable::default();
+    let mut pool = StringPool::default();
+    let mut sentence_indices: HashMap<u32, u32> = HashMap::new();
+
+    table.link_offsets.push(0);
+    for (entry_id, mut links) in candidates {
+        // Checked examples first, then in corpus order
+        links.sort_by_key(|link| !link.0);
+        links.truncate(MAX_EXAMPLES_PER_ENTRY);
+
+        for (_, sentence_id, sense, highlight) in links {
+            let sentence = *sentence_indices.entry(sentence_id).or_insert_with(|| {
+                let (japanese, english) = pairs[&sentence_id];
+                let index = (table.sentences.len() / 3) as u32;
+                table.sentences.extend([sentence_id, pool.get_or_insert(japanese), pool.get_or_insert(english)]);
+                index
+            });
+            table.links.extend([sentence, sense, pool.get_or_insert(highlight)]);
+        }
+
+        table.entry_ids.push(entry_id);
+        table.link_offsets.push((table.links.len() / 3) as u32);
+    }
+
+    table.strings = pool.data;
+    table.string_offsets = pool.offsets;
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_part_of_a_token() {
+        let token = parse_token("彼(かれ)[01]{彼の}~").unwrap();
+        assert_eq!(token.headword, "彼");
+        assert_eq!(token.reading, Some("かれ"));
+        assert_eq!(token.sense, Some(1));
+        assert_eq!(token.surface, Some("彼の"));
+        assert!(token.verified);
+    }
+
+    #[test]
+    fn leaves_missing_parts_empty() {
+        let token = parse_token("は").unwrap();
+        assert_eq!(token.headword, "は");
+        assert_eq!((token.reading, token.sense, token.surface), (None, None, None));
+        assert!(!token.verified);
+
+        let token = parse_token("食べる{食べた}").unwrap();
+        assert_eq!(token.headword, "食べる");
+        assert_eq!(token.surface, Some("食べた"));
+        assert_eq!(token.reading, None);
+    }
+
+    #[test]
+    fn rejects_tokens_without_a_headword() {
+        assert!(parse_token("").is_none());
+        assert!(parse_token("(かれ)").is_none());
+        assert!(parse_token("~").is_none());
+    }
+}
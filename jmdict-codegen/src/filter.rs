@@ -0,0 +1,193 @@
+//! Subset selection for codegen: which words, forms and senses to keep
+//!
+//! Filters are applied while streaming, so the kept words stay in JMdict order.
+
+use crate::jmdict::{Sense, Word};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Default)]
+pub struct WordFilter {
+    /// Keep only words with a form marked common
+    pub common_only: bool,
+    /// Keep only words with a form carrying one of these priority tags (news1, ichi1, ...)
+    pub priorities: Vec<String>,
+    /// Keep only words with a sense matching one of these POS tags; a trailing
+    /// `*` matches any tag with that prefix (`v5*`)
+    pub pos_patterns: Vec<String>,
+    /// Drop forms and senses carrying any of these tags (arch, obs, iK, ...);
+    /// words left without a reading or sense are dropped
+    pub exclude_tags: Vec<String>,
+    /// Keep only these JMdict ids
+    pub ids: Option<HashSet<String>>,
+}
+
+/// Read JMdict ids, one per line; blank lines and `#` comments are skipped.
+pub fn read_ids_file(path: &Path) -> std::io::Result<HashSet<String>> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+fn pos_matches(pattern: &str, pos: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => pos.starts_with(prefix),
+        None => pattern == pos,
+    }
+}
+
+fn has_any(tags: &Option<Vec<String>>, wanted: &[String]) -> bool {
+    tags.iter().flatten().any(|tag| wanted.contains(tag))
+}
+
+impl WordFilter {
+    pub fn is_empty(&self) -> bool {
+        !self.common_only
+            && self.priorities.is_empty()
+            && self.pos_patterns.is_empty()
+            && self.exclude_tags.is_empty()
+            && self.ids.is_none()
+    }
+
+    fn excludes_sense(&self, sense: &Sense) -> bool {
+        [&sense.misc, &sense.field, &sense.dialect, &sense.tags]
+            .into_iter()
+            .any(|tags| has_any(tags, &self.exclude_tags))
+    }
+
+    /// The word as it should be packed, or `None` if it is filtered out.
+    pub fn apply(&self, mut word: Word) -> Option<Word> {
+        if self.ids.as_ref().is_some_and(|ids| !ids.contains(&word.id)) {
+            return None;
+        }
+        if self.common_only && !word.is_common() {
+            return None;
+        }
+        if !self.priorities.is_empty() {
+            let has_priority = word.kanji.iter().flatten().map(|k| &k.priority)
+                .chain(word.kana.iter().map(|k| &k.priority))
+                .any(|priority| has_any(priority, &self.priorities));
+            if !has_priority {
+                return None;
+            }
+        }
+
+        if !self.exclude_tags.is_empty() {
+            if let Some(kanji) = &mut word.kanji {
+                kanji.retain(|k| !has_any(&k.tags, &self.exclude_tags));
+            }
+            word.kana.retain(|k| !has_any(&k.tags, &self.exclude_tags));
+            word.sense.retain(|sense| !self.excludes_sense(sense));
+            if word.kana.is_empty() || word.sense.is_empty() {
+                return None;
+            }
+        }
+
+        if !self.pos_patterns.is_empty() {
+            let has_pos = word.sense.iter()
+                .flat_map(|sense| sense.part_of_speech.iter().flatten())
+                .any(|pos| self.pos_patterns.iter().any(|pattern| pos_matches(pattern, pos)));
+            if !has_pos {
+                return None;
+            }
+        }
+
+        Some(word)
+    }
+
+    /// Short description of the active filters, recorded in the generated metadata
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.common_only {
+            parts.push("common-only".to_string());
+        }
+        if !self.priorities.is_empty() {
+            parts.push(format!("priority={}", self.priorities.join(",")));
+        }
+        if !self.pos_patterns.is_empty() {
+            parts.push(format!("pos={}", self.pos_patterns.join(",")));
+        }
+        if !self.exclude_tags.is_empty() {
+            parts.push(format!("exclude-tags={}", self.exclude_tags.join(",")));
+        }
+        if let Some(ids) = &self.ids {
+            parts.push(format!("ids={}", ids.len()));
+        }
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 食べる: a common kanji form, an archaic kana form, and an archaic sense
+    fn word() -> Word {
+        serde_json::from_value(json!({
+            "id": "1358280",
+            "kanji": [{ "text": "食べる", "common": true, "tags": [], "priority": ["ichi1"] }],
+            "kana": [
+                { "text": "たべる", "common": true, "tags": [] },
+                { "text": "たぶ", "common": false, "tags": ["ok"] },
+            ],
+            "sense": [
+                { "gloss": [{ "lang": "eng", "text": "to eat" }], "partOfSpeech": ["v1", "vt"] },
+                { "gloss": [{ "lang": "eng", "text": "to live on" }], "partOfSpeech": ["v1"], "misc": ["arch"] },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn matches_pos_tags_exactly_or_by_prefix() {
+        assert!(pos_matches("v1", "v1"));
+        assert!(!pos_matches("v1", "v1-s"));
+        assert!(pos_matches("v5*", "v5k"));
+        assert!(pos_matches("v*", "v1"));
+        assert!(!pos_matches("v5*", "v1"));
+    }
+
+    #[test]
+    fn keeps_everything_without_filters() {
+        let filter = WordFilter::default();
+        assert!(filter.is_empty());
+        let kept = filter.apply(word()).unwrap();
+        assert_eq!(kept.kana.len(), 2);
+        assert_eq!(kept.sense.len(), 2);
+    }
+
+    #[test]
+    fn selects_words_by_pos_priority_and_id() {
+        let keeps = |filter: WordFilter| filter.apply(word()).is_some();
+        assert!(keeps(WordFilter { pos_patterns: strings(&["n", "v1*"]), ..WordFilter::default() }));
+        assert!(!keeps(WordFilter { pos_patterns: strings(&["v5*"]), ..WordFilter::default() }));
+        assert!(keeps(WordFilter { priorities: strings(&["ichi1"]), ..WordFilter::default() }));
+        assert!(!keeps(WordFilter { priorities: strings(&["news1"]), ..WordFilter::default() }));
+        assert!(keeps(WordFilter { common_only: true, ..WordFilter::default() }));
+        assert!(!keeps(WordFilter { ids: Some(HashSet::from(["1000000".to_string()])), ..WordFilter::default() }));
+    }
+
+    #[test]
+    fn drops_excluded_forms_and_senses() {
+        let filter = WordFilter { exclude_tags: strings(&["ok", "arch"]), ..WordFilter::default() };
+        let kept = filter.apply(word()).unwrap();
+        assert_eq!(kept.kana.iter().map(|k| k.text.as_str()).collect::<Vec<_>>(), ["たべる"]);
+        assert_eq!(kept.sense.len(), 1);
+        assert_eq!(kept.sense[0].gloss[0].text, "to eat");
+
+        // A word left without senses is dropped
+        let mut archaic = word();
+        archaic.sense[0].misc = Some(strings(&["arch"]));
+        assert!(filter.apply(archaic).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Streaming reader for the jmdict-simplified JMdict JSON
+//!
+//! The `words` array is deserialized one word at a time and handed to a
+//! callback, so the full word list is never held in memory. JMnedict has the
+//! same layout and is read the same way (see `names`).
+
+use serde::de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::io::{BufReader, Read};
+
+/// The top-level fields of the JMdict file that go into the generated
+/// metadata. `words` is streamed separately, and the rest (`commonOnly`,
+/// `tags`...) is skipped.
+#[derive(Debug, Default)]
+pub struct Header {
+    pub dict_date: String,
+    pub version: String,
+    pub languages: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Word {
+    pub id: String,
+    pub kanji: Option<Vec<KanjiEntry>>,
+    pub kana: Vec<KanaEntry>,
+    pub sense: Vec<Sense>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KanjiEntry {
+    pub text: String,
+    pub common: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    /// Priority tags (news1, ichi1, ...), only in sources that keep them
+    pub priority: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KanaEntry {
+    pub text: String,
+    pub common: Option<bool>,
+    #[serde(rename = "appliesToKanji")]
+    pub applies_to_kanji: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    pub priority: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Sense {
+    pub gloss: Vec<Gloss>,
+    #[serde(rename = "partOfSpeech")]
+    pub part_of_speech: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+    pub field: Option<Vec<String>>,
+    pub dialect: Option<Vec<String>>,
+    pub misc: Option<Vec<String>>,
+    pub info: Option<Vec<String>>,
+    pub related: Option<Vec<Xref>>,
+    pub antonym: Option<Vec<Xref>>,
+}
+
+/// A cross-reference to another word: its written form, then optionally a
+/// reading and a sense number (`["橋", "はし", 1]`)
+#[derive(Debug, Deserialize)]
+pub struct Xref(pub Vec<XrefPart>);
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum XrefPart {
+    Text(String),
+    #[allow(dead_code)]
+    Sense(u32),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Gloss {
+    pub lang: String,
+    pub text: String,
+}
+
+impl Word {
+    /// Any kanji or kana form is marked common
+    pub fn is_common(&self) -> bool {
+        self.kanji.iter().flatten().any(|k| k.common.unwrap_or(false))
+            || self.kana.iter().any(|k| k.common.unwrap_or(false))
+    }
+}
+
+impl Xref {
+    /// The written form and, if given, the reading it refers to
+    pub fn target(&self) -> Option<(&str, Option<&str>)> {
+        let mut texts = self.0.iter().filter_map(|part| match part {
+            XrefPart::Text(text) => Some(text.as_str()),
+            XrefPart::Sense(_) => None,
+        });
+        Some((texts.next()?, texts.next()))
+    }
+}
+
+/// Deserialize a JMdict (or JMnedict) document, calling `on_word` for each
+/// word in file order.
+pub fn for_each_word<R: Read, T: DeserializeOwned>(reader: R, on_word: impl FnMut(T)) -> serde_json::Result<Header> {
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+    let header = deserializer.deserialize_map(JmdictVisitor { on_word, word: PhantomData })?;
+    deserializer.end()?;
+    Ok(header)
+}
+
+struct JmdictVisitor<F, T> {
+    on_word: F,
+    word: PhantomData<fn(T)>,
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(T)> Visitor<'de> for JmdictVisitor<F, T> {
+    type Value = Header;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a jmdict-simplified JMdict or JMnedict object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Header, A::Error> {
+        let mut header = Header::default();
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "words" => map.next_value_seed(WordsSeed { on_word: &mut self.on_word, word: PhantomData })?,
+                "dictDate" => header.dict_date = map.next_value()?,
+                "version" => header.version = map.next_value()?,
+                "languages" => header.languages = map.next_value()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(header)
+    }
+}
+
+struct WordsSeed<'a, F, T> {
+    on_word: &'a mut F,
+    word: PhantomData<fn(T)>,
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(T)> DeserializeSeed<'de> for WordsSeed<'_, F, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(T)> Visitor<'de> for WordsSeed<'_, F, T> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of words")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(word) = seq.next_element::<T>()? {
+            (self.on_word)(word);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! KANJIDIC2 ingestion from the jmdict-simplified `kanjidic2-en` JSON export
+//!
+//! Produces a packed kanji table sorted by code point so the runtime can
+//! binary search it.
+
+use crate::pool::{checked_count, StringPool};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct Kanjidic {
+    pub characters: Vec<Character>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Character {
+    literal: String,
+    radicals: Vec<Radical>,
+    misc: Misc,
+    #[serde(rename = "readingMeaning")]
+    reading_meaning: Option<ReadingMeaning>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Radical {
+    #[serde(rename = "type")]
+    kind: String,
+    value: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Misc {
+    grade: Option<u8>,
+    #[serde(rename = "strokeCounts")]
+    stroke_counts: Vec<u8>,
+    frequency: Option<u16>,
+    #[serde(rename = "jlptLevel")]
+    jlpt_level: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadingMeaning {
+    groups: Vec<ReadingMeaningGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadingMeaningGroup {
+    readings: Vec<Reading>,
+    meanings: Vec<Meaning>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reading {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Meaning {
+    lang: String,
+    value: String,
+}
+
+/// Packed kanji table, laid out like the word tables: null-terminated
+/// strings referenced by index, and one variable-length record per kanji.
+#[derive(Default)]
+pub struct KanjiTable {
+    pub strings: Vec<u8>,
+    pub string_offsets: Vec<u32>,
+    pub entries: Vec<u8>,
+    pub entry_offsets: Vec<u32>,
+    pub literals: Vec<u32>,
+}
+
+pub fn pack(kanjidic: &Kanjidic) -> KanjiTable {
+    let mut pool = StringPool::default();
+
+    let mut characters: Vec<&Character> = kanjidic.characters
+        .iter()
+        .filter(|c| c.literal.chars().count() == 1)
+        .collect();
+    characters.sort_by_key(|c| c.literal.chars().next().map(u32::from));
+
+    let mut entries = Vec::new();
+    let mut entry_offsets = Vec::new();
+    let mut literals = Vec::new();
+
+    for character in characters {
+        let mut on = Vec::new();
+        let mut kun = Vec::new();
+        let mut meanings = Vec::new();
+
+        for group in character.reading_meaning.iter().flat_map(|rm| &rm.groups) {
+            for reading in &group.readings {
+                match reading.kind.as_str() {
+                    "ja_on" => on.push(pool.get_or_insert(&reading.value)),
+                    "ja_kun" => kun.push(pool.get_or_insert(&reading.value)),
+                    _ => {}
+                }
+            }
+            for meaning in group.meanings.iter().filter(|m| m.lang == "en") {
+                meanings.push(pool.get_or_insert(&meaning.value));
+            }
+        }
+
+        let radical = character.radicals
+            .iter()
+            .find(|r| r.kind == "classical")
+            .map_or(0, |r| r.value as u8);
+
+        literals.push(character.literal.chars().next().map_or(0, u32::from));
+        entry_offsets.push(entries.len() as u32);
+
+        // Pack entry: stroke_count(1) + grade(1) + jlpt(1) + frequency(2) + radical(1)
+        // + on_count(1) + kun_count(1) + meaning_count(1) + indices...
+        // Missing grade/jlpt/frequency are stored as 0
+        entries.push(character.misc.stroke_counts.first().copied().unwrap_or(0));
+        entries.push(character.misc.grade.unwrap_or(0));
+        entries.push(character.misc.jlpt_level.unwrap_or(0));
+        entries.extend(character.misc.frequency.unwrap_or(0).to_le_bytes());
+        entries.push(radical);
+        entries.push(checked_count(on.len(), "on readings", &character.literal));
+        entries.push(checked_count(kun.len(), "kun readings", &character.literal));
+        entries.push(checked_count(meanings.len(), "meanings", &character.literal));
+
+        for idx in on.iter().chain(&kun).chain(&meanings) {
+            entries.extend(idx.to_le_bytes());
+        }
+    }
+
+    KanjiTable { strings: pool.data, string_offsets: pool.offsets, entries, entry_offsets, literals }
+}
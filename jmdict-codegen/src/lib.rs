@@ -1 +1,8 @@
-// This crate only generates data, no library code
\ No newline at end of file
+//! The data writer and string pool. The codegen binary uses them through
+//! here, and the tests of the crates that read the data use them (and the
+//! sample dictionaries built with them) to check that their readers accept
+//! what codegen writes.
+
+pub mod output;
+pub mod pool;
+pub mod sample;
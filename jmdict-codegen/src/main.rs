@@ -1,315 +1,402 @@
+mod accents;
+mod archive;
+mod details;
+mod examples;
+mod filter;
+mod jmdict;
+mod kanjidic;
+mod names;
+mod radicals;
+mod xrefs;
+
+use accents::AccentTable;
 use clap::Parser;
-use serde::Deserialize;
+use filter::WordFilter;
+use jmdict_codegen::{output, pool};
+use kanjidic::Kanjidic;
+use jmdict::Word;
+use output::{DataWriter, Format, OutputFile};
+use pool::{checked_count, StringPool};
+use radicals::{Kradfile, Radkfile};
 use std::collections::HashMap;
-use std::fs;
-use std::io::Read;
-use flate2::read::GzDecoder;
-use tar::Archive;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "generate_dictionary")]
+#[command(about = "Generate static dictionary data from JMDict")]
+struct Args {
+    /// jmdict-simplified JMdict release (.json, .json.gz, .tgz or .zip)
+    #[arg(long)]
+    input: PathBuf,
+
+    /// Where to write the generated data [default: ../dictionary-data/src/lib.rs
+    /// for rust, ../dictionary-data/dictionary.bin for binary]. A path ending in
+    /// .gz is gzip-compressed.
+    #[arg(long)]
+    output: Option<PathBuf>,
 
-#[derive(Debug, Deserialize)]
-struct JMDict {
-    #[serde(rename = "commonOnly")]
-    #[allow(dead_code)]
+    /// Output format
+    #[arg(long, value_enum, default_value = "rust")]
+    format: Format,
+
+    /// Keep at most this many words (0 for all), after filtering
+    #[arg(long, default_value = "0")]
+    limit: usize,
+
+    /// Keep only words with a form marked common
+    #[arg(long)]
     common_only: bool,
-    #[serde(rename = "dictDate")]
-    #[allow(dead_code)]
-    dict_date: String,
-    #[allow(dead_code)]
-    version: String,
-    #[allow(dead_code)]
+
+    /// Keep only words with one of these priority tags, e.g. news1,ichi1
+    /// (needs a source that keeps priority tags)
+    #[arg(long, value_delimiter = ',')]
+    priority: Vec<String>,
+
+    /// Keep only words with a sense of one of these parts of speech, e.g. v1,v5*
+    #[arg(long, value_delimiter = ',')]
+    pos: Vec<String>,
+
+    /// Drop senses and forms tagged with any of these, e.g. arch,obs
+    #[arg(long, value_delimiter = ',')]
+    exclude_tags: Vec<String>,
+
+    /// Keep only the JMdict ids listed in this file, one per line
+    #[arg(long)]
+    ids_file: Option<PathBuf>,
+
+    /// Gloss languages to keep, as JMdict language codes (eng, ger, fre, rus, spa, dut, ...)
+    #[arg(long, value_delimiter = ',', default_value = "eng")]
     languages: Vec<String>,
-    words: Vec<Word>,
-}
 
-#[derive(Debug, Deserialize)]
-struct Word {
-    id: String,
-    kanji: Option<Vec<KanjiEntry>>,
-    kana: Vec<KanaEntry>,
-    sense: Vec<Sense>,
-}
+    /// Kanjium-style pitch accent TSV (kanji, reading, accent)
+    #[arg(long)]
+    accents: Option<PathBuf>,
 
-#[derive(Debug, Deserialize)]
-struct KanjiEntry {
-    text: String,
-    common: Option<bool>,
-    #[allow(dead_code)]
-    tags: Option<Vec<String>>,
-    #[allow(dead_code)]
-    priority: Option<Vec<String>>,
-}
+    /// KANJIDIC2 in jmdict-simplified JSON form (.json or .json.tgz)
+    #[arg(long)]
+    kanjidic: Option<PathBuf>,
 
-#[derive(Debug, Deserialize)]
-struct KanaEntry {
-    text: String,
-    common: Option<bool>,
-    #[allow(dead_code)]
-    tags: Option<Vec<String>>,
-    #[allow(dead_code)]
-    priority: Option<Vec<String>>,
-}
+    /// RADKFILE in jmdict-simplified JSON form (.json or .json.tgz)
+    #[arg(long)]
+    radkfile: Option<PathBuf>,
 
-#[derive(Debug, Deserialize)]
-struct Sense {
-    gloss: Vec<Gloss>,
-    #[serde(rename = "partOfSpeech")]
-    part_of_speech: Option<Vec<String>>,
-    #[allow(dead_code)]
-    tags: Option<Vec<String>>,
-    #[allow(dead_code)]
-    misc: Option<Vec<String>>,
-    #[allow(dead_code)]
-    info: Option<Vec<String>>,
-}
+    /// KRADFILE in jmdict-simplified JSON form (.json or .json.tgz)
+    #[arg(long)]
+    kradfile: Option<PathBuf>,
 
-#[derive(Debug, Deserialize)]
-struct Gloss {
-    lang: String,
-    text: String,
-}
+    /// JMnedict in jmdict-simplified JSON form (.json or .json.tgz); capped by --limit too
+    #[arg(long)]
+    jmnedict: Option<PathBuf>,
 
-#[derive(Parser)]
-#[command(name = "generate_dictionary")]
-#[command(about = "Generate static dictionary data from JMDict")]
-struct Args {
-    #[arg(long, default_value = "0")]
-    limit: usize,
+    /// Tatoeba Japanese-English sentence pairs TSV
+    #[arg(long, requires = "tatoeba_index")]
+    tatoeba_pairs: Option<PathBuf>,
+
+    /// Tatoeba Japanese indices (jpn_indices.csv) linking sentences to headwords
+    #[arg(long, requires = "tatoeba_pairs")]
+    tatoeba_index: Option<PathBuf>,
 }
 
 fn main() {
     let args = Args::parse();
     
-    // For web builds, limit to common words only to reduce WASM size
-    let limit_for_web = std::env::var("CARGO_CFG_TARGET_ARCH")
-        .map(|arch| arch == "wasm32")
-        .unwrap_or(false);
-    
-    let effective_limit = if limit_for_web && args.limit == 0 {
-        15000 // Only most common words for web to keep WASM size reasonable
-    } else {
-        args.limit
+    let word_filter = WordFilter {
+        common_only: args.common_only,
+        priorities: args.priority.clone(),
+        pos_patterns: args.pos.clone(),
+        exclude_tags: args.exclude_tags.clone(),
+        ids: args.ids_file.as_ref().map(|path| filter::read_ids_file(path).expect("Failed to read ids file")),
     };
     
-    let tgz_data = include_bytes!("../assets/jmdict-eng-3.6.1+20250818123231.json.tgz");
-    let decoder = GzDecoder::new(&tgz_data[..]);
-    let mut archive = Archive::new(decoder);
-    
-    let mut json_content = String::new();
-    let entries = archive.entries().expect("Failed to read tar entries");
-    
-    for entry_result in entries {
-        let mut entry = entry_result.expect("Failed to read tar entry");
-        let path = entry.header().path().expect("Failed to read entry path");
-        
-        if let Some(path_str) = path.to_str() {
-            if path_str.ends_with(".json") {
-                entry.read_to_string(&mut json_content).expect("Failed to read JSON content");
-                break;
-            }
-        }
-    }
-    
-    let jmdict: JMDict = serde_json::from_str(&json_content).expect("Failed to parse JSON");
-    
-    // String pools for deduplication
-    let mut kanji_pool: HashMap<String, u32> = HashMap::new();
-    let mut kana_pool: HashMap<String, u32> = HashMap::new(); 
-    let mut english_pool: HashMap<String, u32> = HashMap::new();
-    let mut pos_pool: HashMap<String, u32> = HashMap::new();
-    let mut id_pool: HashMap<String, u32> = HashMap::new();
+    let accent_table = args.accents.as_ref().map(|path| {
+        let table = AccentTable::load(path).expect("Failed to read pitch accent file");
+        println!("Loaded {} pitch accent entries", table.len());
+        table
+    });
     
-    let mut kanji_strings = Vec::new();
-    let mut kana_strings = Vec::new();
-    let mut english_strings = Vec::new(); 
-    let mut pos_strings = Vec::new();
-    let mut id_strings = Vec::new();
+    // One deduplicated pool for all JMdict strings, so entries can be packed
+    // as soon as a word is read
+    let mut pool = StringPool::default();
     
-    fn get_or_insert(pool: &mut HashMap<String, u32>, strings: &mut Vec<String>, s: &str) -> u32 {
-        if let Some(&idx) = pool.get(s) {
-            idx
-        } else {
-            let idx = strings.len() as u32;
-            pool.insert(s.to_string(), idx);
-            strings.push(s.to_string());
-            idx
-        }
-    }
+    let mut entries_data = Vec::new();
+    let mut entry_offsets = Vec::new();
+    let mut entry_ids = Vec::new();
+    let mut details = Vec::new();
+    let mut detail_offsets = Vec::new();
     
-    let mut word_entries = Vec::new();
+    // Written forms and readings of the packed words, for resolving
+    // cross-references and linking example sentences
+    let mut form_ids: HashMap<String, u32> = HashMap::new();
+    let mut reading_ids: HashMap<(String, String), u32> = HashMap::new();
+    let mut pending_xrefs = Vec::new();
     
-    // Sort words by common status first (common words first), then take the limit
-    let mut words_to_process: Vec<&Word> = jmdict.words.iter().collect();
-    words_to_process.sort_by_key(|word| {
-        // Check if word is common (any kanji or kana entry marked as common)
-        let is_common = word.kanji.as_ref().map_or(false, |kanji_entries| {
-            kanji_entries.iter().any(|k| k.common.unwrap_or(false))
-        }) || word.kana.iter().any(|k| k.common.unwrap_or(false));
+    let mut pack_word = |word: Word| {
+        let id_idx = pool.get_or_insert(&word.id);
+        entry_ids.push(id_idx);
         
-        // Sort common words first (false sorts before true, so negate)
-        !is_common
-    });
-    
-    let word_count = if effective_limit > 0 { effective_limit.min(words_to_process.len()) } else { words_to_process.len() };
-    
-    for (_i, word) in words_to_process.iter().take(word_count).enumerate() {
-        let id_idx = get_or_insert(&mut id_pool, &mut id_strings, &word.id);
+        if let Ok(id) = word.id.parse::<u32>() {
+            for kanji_entry in word.kanji.iter().flatten() {
+                form_ids.entry(kanji_entry.text.clone()).or_insert(id);
+                for kana_entry in &word.kana {
+                    reading_ids.entry((kanji_entry.text.clone(), kana_entry.text.clone())).or_insert(id);
+                }
+            }
+            for kana_entry in &word.kana {
+                form_ids.entry(kana_entry.text.clone()).or_insert(id);
+            }
+            
+            let references = word.sense.iter().flat_map(|sense| {
+                let related = sense.related.iter().flatten().map(|xref| (xrefs::KIND_RELATED, xref));
+                let antonyms = sense.antonym.iter().flatten().map(|xref| (xrefs::KIND_ANTONYM, xref));
+                related.chain(antonyms)
+            });
+            for (kind, xref) in references {
+                if let Some((form, reading)) = xref.target() {
+                    pending_xrefs.push(xrefs::PendingXref {
+                        source: id,
+                        kind,
+                        form: form.to_string(),
+                        reading: reading.map(str::to_string),
+                    });
+                }
+            }
+        }
         
         let mut kanji_indices = Vec::new();
         let mut kana_indices = Vec::new();
-        let mut english_indices = Vec::new();
+        let mut gloss_indices = Vec::new();
+        let mut gloss_languages = Vec::new();
+        let mut gloss_senses = Vec::new();
         let mut pos_indices = Vec::new();
         
-        // Check if word is common (any kanji or kana entry marked as common)
-        let mut is_common = false;
-        
         // Process kanji
-        if let Some(kanji_entries) = &word.kanji {
-            for kanji_entry in kanji_entries {
-                kanji_indices.push(get_or_insert(&mut kanji_pool, &mut kanji_strings, &kanji_entry.text));
-                if kanji_entry.common.unwrap_or(false) {
-                    is_common = true;
-                }
-            }
+        for kanji_entry in word.kanji.iter().flatten() {
+            kanji_indices.push(pool.get_or_insert(&kanji_entry.text));
         }
         
         // Process kana
+        let mut reading_accents = Vec::new();
         for kana_entry in &word.kana {
-            kana_indices.push(get_or_insert(&mut kana_pool, &mut kana_strings, &kana_entry.text));
-            if kana_entry.common.unwrap_or(false) {
-                is_common = true;
-            }
+            kana_indices.push(pool.get_or_insert(&kana_entry.text));
+            
+            // Pitch accent for this reading, keyed by any kanji form it applies to
+            let accents = accent_table.as_ref().and_then(|table| {
+                let written_forms = word.kanji.iter().flatten()
+                    .map(|k| k.text.as_str())
+                    .filter(|text| kana_entry.applies_to_kanji.as_ref().is_none_or(|applies| {
+                        applies.iter().any(|a| a == "*" || a == text)
+                    }));
+                table.lookup(written_forms, &kana_entry.text)
+            });
+            reading_accents.push(accents.map(|a| a.to_vec()).unwrap_or_default());
         }
         
-        // Process senses
+        // Process senses, numbering only those with glosses in the selected languages
+        let mut sense_count = 0;
         for sense in &word.sense {
-            // English glosses
+            // Glosses in the selected languages, tagged with their language and sense index
+            let first_gloss = gloss_indices.len();
             for gloss in &sense.gloss {
-                if gloss.lang == "eng" {
-                    english_indices.push(get_or_insert(&mut english_pool, &mut english_strings, &gloss.text));
+                if let Some(lang_idx) = args.languages.iter().position(|lang| *lang == gloss.lang) {
+                    gloss_indices.push(pool.get_or_insert(&gloss.text));
+                    gloss_languages.push(checked_count::<u8>(lang_idx, "gloss languages", &word.id));
+                    gloss_senses.push(checked_count::<u8>(sense_count, "senses", &word.id));
                 }
             }
+            if gloss_indices.len() > first_gloss {
+                sense_count += 1;
+            }
             
             // POS (only from first sense)
             if pos_indices.is_empty() {
                 if let Some(pos_array) = &sense.part_of_speech {
                     for pos_str in pos_array {
-                        pos_indices.push(get_or_insert(&mut pos_pool, &mut pos_strings, pos_str));
+                        pos_indices.push(pool.get_or_insert(pos_str));
                     }
                 }
             }
         }
         
-        word_entries.push((id_idx, kanji_indices, kana_indices, english_indices, pos_indices, is_common));
-        
-    }
-    
-    
-    // Create packed binary format
-    let mut strings_data = Vec::new();
-    let mut string_offsets = Vec::new();
-    
-    // Pack all strings into one byte array
-    for strings in [&kanji_strings, &kana_strings, &english_strings, &pos_strings, &id_strings] {
-        for s in strings {
-            string_offsets.push(strings_data.len() as u32);
-            strings_data.extend(s.as_bytes());
-            strings_data.push(0); // null terminator
-        }
-    }
-    
-    // Pack entries into binary format
-    let mut entries_data = Vec::new();
-    let mut entry_offsets = Vec::new();
-    
-    for (id_idx, kanji_indices, kana_indices, english_indices, pos_indices, is_common) in &word_entries {
         entry_offsets.push(entries_data.len() as u32);
         
-        // Pack entry: id(4) + kanji_count(1) + kana_count(1) + english_count(1) + pos_count(1) + is_common(1) + indices...
+        // Pack entry: id(4) + kanji_count(2) + kana_count(2) + gloss_count(2) + pos_count(2) + is_common(1) + indices...
+        // followed by one accent group per kana reading: accent_count(1) + positions(1 each)
+        // and one language index per gloss (1 each, into GLOSS_LANGUAGES)
+        // and one sense index per gloss (1 each, counting from 0 in entry order)
         entries_data.extend(id_idx.to_le_bytes());
-        entries_data.push(kanji_indices.len() as u8);
-        entries_data.push(kana_indices.len() as u8);
-        entries_data.push(english_indices.len() as u8);
-        entries_data.push(pos_indices.len() as u8);
-        entries_data.push(if *is_common { 1 } else { 0 });
+        entries_data.extend(checked_count::<u16>(kanji_indices.len(), "kanji forms", &word.id).to_le_bytes());
+        entries_data.extend(checked_count::<u16>(kana_indices.len(), "kana forms", &word.id).to_le_bytes());
+        entries_data.extend(checked_count::<u16>(gloss_indices.len(), "glosses", &word.id).to_le_bytes());
+        entries_data.extend(checked_count::<u16>(pos_indices.len(), "parts of speech", &word.id).to_le_bytes());
+        entries_data.push(if word.is_common() { 1 } else { 0 });
         
-        // Add string indices (adjusted for string pool sections)
-        let kanji_base = 0u32;
-        let kana_base = kanji_strings.len() as u32;
-        let english_base = kana_base + kana_strings.len() as u32;
-        let pos_base = english_base + english_strings.len() as u32;
-        let _id_base = pos_base + pos_strings.len() as u32;
-        
-        for &idx in kanji_indices {
-            entries_data.extend((kanji_base + idx).to_le_bytes());
-        }
-        for &idx in kana_indices {
-            entries_data.extend((kana_base + idx).to_le_bytes());
+        for idx in kanji_indices.iter().chain(&kana_indices).chain(&gloss_indices).chain(&pos_indices) {
+            entries_data.extend(idx.to_le_bytes());
         }
-        for &idx in english_indices {
-            entries_data.extend((english_base + idx).to_le_bytes());
+        for accents in &reading_accents {
+            entries_data.push(checked_count(accents.len(), "pitch accents", &word.id));
+            entries_data.extend(accents);
         }
-        for &idx in pos_indices {
-            entries_data.extend((pos_base + idx).to_le_bytes());
+        entries_data.extend(gloss_languages);
+        entries_data.extend(gloss_senses);
+        
+        detail_offsets.push(details.len() as u32);
+        details::pack(&word, &args.languages, &mut pool, &mut details);
+    };
+    
+    // Stream the words in JMdict order; only the packed entries are kept between words
+    let limit = if args.limit > 0 { args.limit } else { usize::MAX };
+    let mut word_count = 0;
+    let mut seen_priority = false;
+    let header = archive::with_json_reader(&args.input, |reader| {
+        jmdict::for_each_word(reader, |word: Word| {
+            seen_priority |= word.kanji.iter().flatten().any(|k| k.priority.is_some())
+                || word.kana.iter().any(|k| k.priority.is_some());
+            if word_count < limit {
+                if let Some(word) = word_filter.apply(word) {
+                    pack_word(word);
+                    word_count += 1;
+                }
+            }
+        })
+    }).expect("Failed to parse JSON");
+    
+    if !word_filter.is_empty() {
+        println!("Kept {} words ({})", word_count, word_filter.describe());
+    }
+    if !args.priority.is_empty() && !seen_priority {
+        eprintln!("Warning: the JMdict file has no priority tags, so --priority matched nothing");
+    }
+    
+    for lang in &args.languages {
+        if !header.languages.contains(lang) {
+            eprintln!("Warning: the JMdict file has no '{}' glosses (it has: {})", lang, header.languages.join(", "));
         }
     }
     
-    // No more pre-built indices - use runtime caching instead
+    // Form markers and per-sense notes, one run per entry
+    detail_offsets.push(details.len() as u32);
     
+    // Entry indices sorted by JMdict id, for binary-searching an id
+    let mut id_index: Vec<u32> = (0..entry_ids.len() as u32).collect();
+    id_index.sort_by(|&a, &b| pool.get(entry_ids[a as usize]).cmp(pool.get(entry_ids[b as usize])));
     
-    // Generate compact dictionary data without indices
-    let mut rust_code = String::new();
-    rust_code.push_str("// Auto-generated compact dictionary data\n");
+    // Each table is written out (and dropped) as soon as it is complete
+    let output_path = args.output.clone().unwrap_or_else(|| match args.format {
+        Format::Rust => PathBuf::from("../dictionary-data/src/lib.rs"),
+        Format::Binary => PathBuf::from("../dictionary-data/dictionary.bin"),
+    });
+    let output_file = OutputFile::create(&output_path).expect("Failed to create output file");
+    let mut data = DataWriter::new(output_file, args.format);
     
     // Packed binary data
-    rust_code.push_str("pub static JMDICT_STRINGS: &[u8] = &[\n");
-    for chunk in strings_data.chunks(16) {
-        rust_code.push_str("    ");
-        for &b in chunk {
-            rust_code.push_str(&format!("{}, ", b));
-        }
-        rust_code.push_str("\n");
-    }
-    rust_code.push_str("];\n\n");
+    data.u8_array("JMDICT_STRINGS", pool.data);
+    data.u8_array("JMDICT_ENTRIES", entries_data);
+    data.u32_array("JMDICT_ENTRY_OFFSETS", entry_offsets);
+    data.u32_array("JMDICT_STRING_OFFSETS", pool.offsets);
     
-    rust_code.push_str("pub static JMDICT_ENTRIES: &[u8] = &[\n");
-    for chunk in entries_data.chunks(16) {
-        rust_code.push_str("    ");
-        for &b in chunk {
-            rust_code.push_str(&format!("{}, ", b));
-        }
-        rust_code.push_str("\n");
-    }
-    rust_code.push_str("];\n\n");
+    data.u32_array("JMDICT_ID_INDEX", id_index);
+    data.u32_array("ENTRY_DETAILS", details);
+    data.u32_array("ENTRY_DETAIL_OFFSETS", detail_offsets);
+    data.strings("GLOSS_LANGUAGES", args.languages.clone());
     
-    rust_code.push_str("pub static JMDICT_ENTRY_OFFSETS: &[u32] = &[\n");
-    for chunk in entry_offsets.chunks(8) {
-        rust_code.push_str("    ");
-        for &offset in chunk {
-            rust_code.push_str(&format!("{}, ", offset));
-        }
-        rust_code.push_str("\n");
+    // Kanji information table (empty when no KANJIDIC2 file was given)
+    let kanji_table = args.kanjidic.as_ref().map(|path| {
+        let kanjidic: Kanjidic = archive::read_json(path).expect("Failed to parse KANJIDIC2 JSON");
+        let table = kanjidic::pack(&kanjidic);
+        println!("Packed {} kanji from KANJIDIC2", table.literals.len());
+        table
+    }).unwrap_or_default();
+    let kanji_count = kanji_table.literals.len();
+    data.u8_array("KANJIDIC_STRINGS", kanji_table.strings);
+    data.u32_array("KANJIDIC_STRING_OFFSETS", kanji_table.string_offsets);
+    data.u8_array("KANJIDIC_ENTRIES", kanji_table.entries);
+    data.u32_array("KANJIDIC_ENTRY_OFFSETS", kanji_table.entry_offsets);
+    data.u32_array("KANJIDIC_LITERALS", kanji_table.literals);
+    
+    // Radical/component tables (empty when no RADKFILE/KRADFILE was given)
+    let radkfile: Option<Radkfile> = args.radkfile.as_ref().map(|path| {
+        archive::read_json(path).expect("Failed to parse RADKFILE JSON")
+    });
+    let kradfile: Option<Kradfile> = args.kradfile.as_ref().map(|path| {
+        archive::read_json(path).expect("Failed to parse KRADFILE JSON")
+    });
+    let radical_table = radicals::pack(radkfile.as_ref(), kradfile.as_ref());
+    if radkfile.is_some() || kradfile.is_some() {
+        println!("Packed {} radicals and {} kanji decompositions", radical_table.radicals.len(), radical_table.krad_literals.len());
     }
-    rust_code.push_str("];\n\n");
+    data.u32_array("RADICALS", radical_table.radicals);
+    data.u8_array("RADICAL_STROKES", radical_table.radical_strokes);
+    data.u32_array("RADICAL_KANJI_OFFSETS", radical_table.radical_kanji_offsets);
+    data.u32_array("RADICAL_KANJI", radical_table.radical_kanji);
+    data.u32_array("KRAD_LITERALS", radical_table.krad_literals);
+    data.u32_array("KRAD_COMPONENT_OFFSETS", radical_table.krad_component_offsets);
+    data.u32_array("KRAD_COMPONENTS", radical_table.krad_components);
+    
+    // Proper names (empty when no JMnedict file was given)
+    let name_table = args.jmnedict.as_ref().map(|path| {
+        let table = archive::with_json_reader(path, |reader| names::pack(reader, args.limit))
+            .expect("Failed to parse JMnedict JSON");
+        println!("Packed {} names from JMnedict", table.len());
+        table
+    }).unwrap_or_default();
+    let name_count = name_table.len();
+    data.u8_array("NAMES_STRINGS", name_table.strings);
+    data.u32_array("NAMES_STRING_OFFSETS", name_table.string_offsets);
+    data.u8_array("NAMES_ENTRIES", name_table.entries);
+    data.u32_array("NAMES_ENTRY_OFFSETS", name_table.entry_offsets);
     
-    rust_code.push_str("pub static JMDICT_STRING_OFFSETS: &[u32] = &[\n");
-    for chunk in string_offsets.chunks(8) {
-        rust_code.push_str("    ");
-        for &offset in chunk {
-            rust_code.push_str(&format!("{}, ", offset));
+    // Entry id for a written form, narrowed down by its reading when there is one
+    let resolve = |headword: &str, reading: Option<&str>| match reading {
+        Some(reading) => reading_ids
+            .get(&(headword.to_string(), reading.to_string()))
+            .or_else(|| form_ids.get(headword))
+            .copied(),
+        None => form_ids.get(headword).copied(),
+    };
+    
+    // Example sentences (empty when no Tatoeba export was given)
+    let example_table = match (&args.tatoeba_pairs, &args.tatoeba_index) {
+        (Some(pairs_path), Some(index_path)) => {
+            let table = examples::pack(pairs_path, index_path, resolve).expect("Failed to read Tatoeba files");
+            println!("Linked {} example sentences to {} entries", table.sentences.len() / 3, table.entry_ids.len());
+            table
         }
-        rust_code.push_str("\n");
+        _ => examples::ExampleTable::default(),
+    };
+    data.u8_array("EXAMPLE_STRINGS", example_table.strings);
+    data.u32_array("EXAMPLE_STRING_OFFSETS", example_table.string_offsets);
+    data.u32_array("EXAMPLE_SENTENCES", example_table.sentences);
+    data.u32_array("EXAMPLE_ENTRY_IDS", example_table.entry_ids);
+    data.u32_array("EXAMPLE_LINK_OFFSETS", example_table.link_offsets);
+    data.u32_array("EXAMPLE_LINKS", example_table.links);
+    
+    // Related words and antonyms between the packed entries
+    let xref_table = xrefs::pack(&pending_xrefs, resolve);
+    if !xref_table.entry_ids.is_empty() {
+        println!("Linked {} cross-references from {} entries", xref_table.links.len() / 2, xref_table.entry_ids.len());
     }
-    rust_code.push_str("];\n\n");
+    data.u32_array("XREF_ENTRY_IDS", xref_table.entry_ids);
+    data.u32_array("XREF_LINK_OFFSETS", xref_table.link_offsets);
+    data.u32_array("XREF_LINKS", xref_table.links);
+    
+    // Checksum over every table above, verified by japandict_core::validate
+    data.u64_value("DATA_CHECKSUM", data.checksum());
     
     // No more static indices - runtime caching is used instead
     
-    // String pool metadata
-    rust_code.push_str(&format!("pub const KANJI_STRINGS_COUNT: u32 = {};\n", kanji_strings.len()));
-    rust_code.push_str(&format!("pub const KANA_STRINGS_COUNT: u32 = {};\n", kana_strings.len()));
-    rust_code.push_str(&format!("pub const ENGLISH_STRINGS_COUNT: u32 = {};\n", english_strings.len()));
-    rust_code.push_str(&format!("pub const POS_STRINGS_COUNT: u32 = {};\n", pos_strings.len()));
-    rust_code.push_str(&format!("pub const ID_STRINGS_COUNT: u32 = {};\n", id_strings.len()));
-    rust_code.push_str(&format!("pub const WORD_COUNT: usize = {};\n", word_entries.len()));
+    // Table sizes
+    data.usize_count("WORD_COUNT", word_count);
+    data.usize_count("KANJIDIC_COUNT", kanji_count);
+    data.usize_count("NAME_COUNT", name_count);
+    
+    // Where the data came from, for bug reports
+    data.text("JMDICT_VERSION", &header.version);
+    data.text("JMDICT_DATE", &header.dict_date);
+    data.usize_count("ENTRY_LIMIT", args.limit);
+    data.text("CODEGEN_FILTERS", &word_filter.describe());
+    data.text("CODEGEN_VERSION", env!("CARGO_PKG_VERSION"));
 
-    fs::write("../dictionary-data/src/lib.rs", rust_code).expect("Failed to write generated code");
+    data.finish().finish().expect("Failed to write generated data");
+    println!("Wrote {}", output_path.display());
 }
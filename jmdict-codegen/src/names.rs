@@ -0,0 +1,98 @@
+//! JMnedict ingestion from the jmdict-simplified `jmnedict-all` JSON export
+//!
+//! Proper names get their own packed section so they never mix with the
+//! word entries or their string pools. The names are streamed like the
+//! JMdict words, so the export is never held in memory as a whole.
+
+use crate::jmdict;
+use crate::pool::{checked_count, StringPool};
+use serde::Deserialize;
+use std::io::Read;
+
+#[derive(Debug, Deserialize)]
+struct NameWord {
+    id: String,
+    kanji: Vec<NameForm>,
+    kana: Vec<NameForm>,
+    translation: Vec<Translation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameForm {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Translation {
+    /// Name types such as "surname", "place", "given", "company"
+    #[serde(rename = "type")]
+    name_types: Vec<String>,
+    translation: Vec<TranslationText>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslationText {
+    lang: String,
+    text: String,
+}
+
+#[derive(Default)]
+pub struct NameTable {
+    pub strings: Vec<u8>,
+    pub string_offsets: Vec<u32>,
+    pub entries: Vec<u8>,
+    pub entry_offsets: Vec<u32>,
+}
+
+impl NameTable {
+    pub fn len(&self) -> usize {
+        self.entry_offsets.len()
+    }
+}
+
+/// Pack up to `limit` names (0 for all) in JMnedict order.
+pub fn pack(reader: impl Read, limit: usize) -> serde_json::Result<NameTable> {
+    let mut pool = StringPool::default();
+    let mut entries = Vec::new();
+    let mut entry_offsets = Vec::new();
+
+    let limit = if limit > 0 { limit } else { usize::MAX };
+
+    jmdict::for_each_word(reader, |word: NameWord| {
+        if entry_offsets.len() >= limit {
+            return;
+        }
+        let id_idx = pool.get_or_insert(&word.id);
+        let kanji: Vec<u32> = word.kanji.iter().map(|k| pool.get_or_insert(&k.text)).collect();
+        let kana: Vec<u32> = word.kana.iter().map(|k| pool.get_or_insert(&k.text)).collect();
+
+        let mut translations = Vec::new();
+        let mut name_types = Vec::new();
+        for translation in &word.translation {
+            for text in translation.translation.iter().filter(|t| t.lang == "eng") {
+                translations.push(pool.get_or_insert(&text.text));
+            }
+            for name_type in &translation.name_types {
+                let idx = pool.get_or_insert(name_type);
+                if !name_types.contains(&idx) {
+                    name_types.push(idx);
+                }
+            }
+        }
+
+        entry_offsets.push(entries.len() as u32);
+
+        // Pack entry: id(4) + kanji_count(1) + kana_count(1) + translation_count(1) + type_count(1) + indices...
+        entries.extend(id_idx.to_le_bytes());
+        entries.push(checked_count(kanji.len(), "kanji forms", &word.id));
+        entries.push(checked_count(kana.len(), "kana forms", &word.id));
+        entries.push(checked_count(translations.len(), "translations", &word.id));
+        entries.push(checked_count(name_types.len(), "name types", &word.id));
+
+        for idx in kanji.iter().chain(&kana).chain(&translations).chain(&name_types) {
+            entries.extend(idx.to_le_bytes());
+        }
+    })?;
+
+    Ok(NameTable { strings: pool.data, string_offsets: pool.offsets, entries, entry_offsets })
+}
@@ -0,0 +1,242 @@
+//! Generated data as a list of named sections, written either as Rust source
+//! for the dictionary-data crate or as a standalone binary file.
+//!
+//! Tables are written out as soon as they are added, so codegen never holds
+//! a second copy of them; numbers and text (which include the checksum of
+//! the tables) follow the tables when the writer is finished.
+//!
+//! Binary layout (all integers little-endian):
+//! - magic `JAPADICT` (8 bytes), format version (u32)
+//! - sections up to the end of the file, each: name length (u8), name, kind
+//!   (u8), payload length in bytes (u32), zero padding up to a 4-byte
+//!   boundary, payload
+//!
+//! Kinds: 0 = u8 array, 1 = u32 array, 2 = string list (null-terminated),
+//! 3 = number (u64), 4 = text (UTF-8).
+
+use crate::pool::Fnv1a;
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+pub const BINARY_MAGIC: &[u8; 8] = b"JAPADICT";
+/// Bump whenever a section or a packed record changes layout, so older
+/// readers reject the file instead of misreading it
+pub const BINARY_VERSION: u32 = 3;
+
+const KIND_U8_ARRAY: u8 = 0;
+const KIND_U32_ARRAY: u8 = 1;
+const KIND_STRINGS: u8 = 2;
+const KIND_NUMBER: u8 = 3;
+const KIND_TEXT: u8 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Rust source for the dictionary-data crate
+    Rust,
+    /// Section container, for loading at runtime
+    Binary,
+}
+
+enum Value {
+    /// A number, emitted as a `u64` or `usize` const in Rust source
+    Number { value: u64, rust_type: &'static str },
+    /// A single string, emitted as a `&str` const in Rust source
+    Text(String),
+}
+
+pub struct DataWriter<W: Write> {
+    out: W,
+    format: Format,
+    /// Bytes written so far, for aligning binary payloads
+    written: usize,
+    checksum: Fnv1a,
+    values: Vec<(String, Value)>,
+}
+
+impl<W: Write> DataWriter<W> {
+    pub fn new(out: W, format: Format) -> Self {
+        let mut writer = DataWriter { out, format, written: 0, checksum: Fnv1a::default(), values: Vec::new() };
+        match format {
+            Format::Rust => writer.put(b"// Auto-generated compact dictionary data\n"),
+            Format::Binary => {
+                writer.put(BINARY_MAGIC);
+                writer.put(&BINARY_VERSION.to_le_bytes());
+            }
+        }
+        writer
+    }
+
+    pub fn u8_array(&mut self, name: &str, data: Vec<u8>) {
+        self.checksum.update(&data);
+        match self.format {
+            Format::Rust => self.rust_array(name, "u8", &data, 16),
+            Format::Binary => {
+                self.section_header(name, KIND_U8_ARRAY, data.len());
+                self.put(&data);
+            }
+        }
+    }
+
+    pub fn u32_array(&mut self, name: &str, data: Vec<u32>) {
+        for value in &data {
+            self.checksum.update(&value.to_le_bytes());
+        }
+        match self.format {
+            Format::Rust => self.rust_array(name, "u32", &data, 8),
+            Format::Binary => {
+                self.section_header(name, KIND_U32_ARRAY, data.len() * 4);
+                for value in &data {
+                    self.put(&value.to_le_bytes());
+                }
+            }
+        }
+    }
+
+    pub fn strings(&mut self, name: &str, data: Vec<String>) {
+        for value in &data {
+            self.checksum.update(value.as_bytes());
+            self.checksum.update(&[0]);
+        }
+        match self.format {
+            Format::Rust => {
+                let quoted: Vec<String> = data.iter().map(|value| format!("{:?}", value)).collect();
+                self.put(format!("pub static {}: &[&str] = &[{}];\n\n", name, quoted.join(", ")).as_bytes());
+            }
+            Format::Binary => {
+                self.section_header(name, KIND_STRINGS, data.iter().map(|value| value.len() + 1).sum());
+                for value in &data {
+                    self.put(value.as_bytes());
+                    self.put(&[0]);
+                }
+            }
+        }
+    }
+
+    pub fn usize_count(&mut self, name: &str, value: usize) {
+        self.values.push((name.to_string(), Value::Number { value: value as u64, rust_type: "usize" }));
+    }
+
+    pub fn u64_value(&mut self, name: &str, value: u64) {
+        self.values.push((name.to_string(), Value::Number { value, rust_type: "u64" }));
+    }
+
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.values.push((name.to_string(), Value::Text(value.to_string())));
+    }
+
+    /// FNV-1a over the binary payloads of every array and string list section
+    /// added so far, in the order they were added. japandict_core::validate
+    /// hashes its tables in the same order.
+    pub fn checksum(&self) -> u64 {
+        self.checksum.value()
+    }
+
+    /// Write the numbers and text after the tables and hand back the output
+    pub fn finish(mut self) -> W {
+        for (name, value) in std::mem::take(&mut self.values) {
+            match (self.format, value) {
+                (Format::Rust, Value::Number { value, rust_type }) => {
+                    self.put(format!("pub const {}: {} = {};\n", name, rust_type, value).as_bytes());
+                }
+                (Format::Rust, Value::Text(value)) => {
+                    self.put(format!("pub const {}: &str = {:?};\n", name, value).as_bytes());
+                }
+                (Format::Binary, Value::Number { value, .. }) => {
+                    self.section_header(&name, KIND_NUMBER, 8);
+                    self.put(&value.to_le_bytes());
+                }
+                (Format::Binary, Value::Text(value)) => {
+                    self.section_header(&name, KIND_TEXT, value.len());
+                    self.put(value.as_bytes());
+                }
+            }
+        }
+        self.out.flush().expect("Failed to write generated data");
+        self.out
+    }
+
+    fn put(&mut self, bytes: &[u8]) {
+        self.out.write_all(bytes).expect("Failed to write generated data");
+        self.written += bytes.len();
+    }
+
+    fn section_header(&mut self, name: &str, kind: u8, len: usize) {
+        self.put(&[name.len() as u8]);
+        self.put(name.as_bytes());
+        self.put(&[kind]);
+        self.put(&(len as u32).to_le_bytes());
+        let padding = self.written.next_multiple_of(4) - self.written;
+        self.put(&[0; 3][..padding]);
+    }
+
+    fn rust_array<T: std::fmt::Display>(&mut self, name: &str, ty: &str, data: &[T], per_line: usize) {
+        self.put(format!("pub static {}: &[{}] = &[\n", name, ty).as_bytes());
+        for chunk in data.chunks(per_line) {
+            let mut line = String::from("    ");
+            for value in chunk {
+                line.push_str(&format!("{}, ", value));
+            }
+            line.push('\n');
+            self.put(line.as_bytes());
+        }
+        self.put(b"];\n\n");
+    }
+}
+
+/// Where generated data goes: a buffered file, gzip-compressed when its name
+/// ends in `.gz`. It is written under a temporary name and only renamed into
+/// place by `finish`, so a failed run doesn't leave half a file behind.
+pub struct OutputFile {
+    writer: OutputWriter,
+    partial: PathBuf,
+    path: PathBuf,
+}
+
+enum OutputWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutputFile {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        let file = BufWriter::new(File::create(&partial)?);
+        let writer = if path.extension().is_some_and(|ext| ext == "gz") {
+            OutputWriter::Gzip(GzEncoder::new(file, Compression::best()))
+        } else {
+            OutputWriter::Plain(file)
+        };
+        Ok(OutputFile { writer, partial, path: path.to_path_buf() })
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        let mut file = match self.writer {
+            OutputWriter::Plain(file) => file,
+            OutputWriter::Gzip(encoder) => encoder.finish()?,
+        };
+        file.flush()?;
+        fs::rename(&self.partial, &self.path)
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.writer {
+            OutputWriter::Plain(file) => file.write(buf),
+            OutputWriter::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            OutputWriter::Plain(file) => file.flush(),
+            OutputWriter::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
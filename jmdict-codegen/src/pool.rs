@@ -0,0 +1,63 @@
+//! Deduplicating pool of null-terminated strings, addressed by index, and
+//! checked narrowing of list lengths into packed count fields
+
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct StringPool {
+    indices: HashMap<String, u32>,
+    pub data: Vec<u8>,
+    pub offsets: Vec<u32>,
+}
+
+impl StringPool {
+    pub fn get_or_insert(&mut self, s: &str) -> u32 {
+        if let Some(&idx) = self.indices.get(s) {
+            return idx;
+        }
+        let idx = self.offsets.len() as u32;
+        self.offsets.push(self.data.len() as u32);
+        self.data.extend(s.as_bytes());
+        self.data.push(0);
+        self.indices.insert(s.to_string(), idx);
+        idx
+    }
+    
+    /// The string at `idx`, without its terminator
+    pub fn get(&self, idx: u32) -> &[u8] {
+        let start = self.offsets[idx as usize] as usize;
+        let len = self.data[start..].iter().position(|&b| b == 0).unwrap_or(self.data.len() - start);
+        &self.data[start..start + len]
+    }
+}
+
+/// Narrow a list length to its packed count field, failing instead of
+/// silently wrapping when the list is too long.
+pub fn checked_count<T: TryFrom<usize>>(len: usize, what: &str, id: &str) -> T {
+    T::try_from(len).unwrap_or_else(|_| {
+        panic!("{} of {} has {} items, more than its packed count field can hold", what, id, len)
+    })
+}
+
+/// 64-bit FNV-1a, fed one piece at a time so a checksum can be taken while
+/// the data is written out
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+}
+
+impl Fnv1a {
+    pub fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,88 @@
+//! RADKFILE/KRADFILE ingestion from the jmdict-simplified JSON exports
+//!
+//! RADKFILE maps each radical to the kanji containing it and drives the
+//! component lookup; KRADFILE maps each kanji to its components.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct Radkfile {
+    radicals: BTreeMap<String, RadicalInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RadicalInfo {
+    #[serde(rename = "strokeCount")]
+    stroke_count: u8,
+    kanji: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Kradfile {
+    kanji: BTreeMap<String, Vec<String>>,
+}
+
+/// Code point tables. Variable-length lists are stored flat with an offsets
+/// array holding one extra trailing entry, so list `i` is
+/// `values[offsets[i]..offsets[i + 1]]`.
+#[derive(Default)]
+pub struct RadicalTable {
+    pub radicals: Vec<u32>,
+    pub radical_strokes: Vec<u8>,
+    pub radical_kanji_offsets: Vec<u32>,
+    pub radical_kanji: Vec<u32>,
+    pub krad_literals: Vec<u32>,
+    pub krad_component_offsets: Vec<u32>,
+    pub krad_components: Vec<u32>,
+}
+
+fn code_point(s: &str) -> Option<u32> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c as u32),
+        _ => None,
+    }
+}
+
+pub fn pack(radkfile: Option<&Radkfile>, kradfile: Option<&Kradfile>) -> RadicalTable {
+    let mut table = RadicalTable::default();
+
+    if let Some(radkfile) = radkfile {
+        // Picker order: by stroke count, then code point
+        let mut radicals: Vec<(u32, &RadicalInfo)> = radkfile.radicals
+            .iter()
+            .filter_map(|(radical, info)| Some((code_point(radical)?, info)))
+            .collect();
+        radicals.sort_by_key(|(c, info)| (info.stroke_count, *c));
+
+        table.radical_kanji_offsets.push(0);
+        for (radical, info) in radicals {
+            let mut kanji: Vec<u32> = info.kanji.iter().filter_map(|k| code_point(k)).collect();
+            kanji.sort_unstable();
+            kanji.dedup();
+
+            table.radicals.push(radical);
+            table.radical_strokes.push(info.stroke_count);
+            table.radical_kanji.extend(kanji);
+            table.radical_kanji_offsets.push(table.radical_kanji.len() as u32);
+        }
+    }
+
+    if let Some(kradfile) = kradfile {
+        let mut kanji: Vec<(u32, &Vec<String>)> = kradfile.kanji
+            .iter()
+            .filter_map(|(literal, components)| Some((code_point(literal)?, components)))
+            .collect();
+        kanji.sort_by_key(|(c, _)| *c);
+
+        table.krad_component_offsets.push(0);
+        for (literal, components) in kanji {
+            table.krad_literals.push(literal);
+            table.krad_components.extend(components.iter().filter_map(|c| code_point(c)));
+            table.krad_component_offsets.push(table.krad_components.len() as u32);
+        }
+    }
+
+    table
+}
@@ -0,0 +1,130 @@
+//! Small dictionaries in the binary format, packed the way codegen packs real
+//! data, for the tests of the crates that read it
+
+use crate::output::{DataWriter, Format};
+use crate::pool::StringPool;
+
+/// A word with all its glosses in one sense, tagged `n` (noun), with a
+/// heiban accent on every reading
+pub struct SampleWord {
+    pub id: String,
+    pub kanji: Vec<String>,
+    pub kana: Vec<String>,
+    /// (JMdict language code, gloss) pairs
+    pub glosses: Vec<(String, String)>,
+    pub common: bool,
+}
+
+/// A common word with at most one kanji form and one reading
+pub fn word(id: &str, kanji: Option<&str>, kana: &str, glosses: &[(&str, &str)]) -> SampleWord {
+    SampleWord {
+        id: id.to_string(),
+        kanji: kanji.into_iter().map(str::to_string).collect(),
+        kana: vec![kana.to_string()],
+        glosses: glosses.iter().map(|&(lang, text)| (lang.to_string(), text.to_string())).collect(),
+        common: true,
+    }
+}
+
+/// The words in the binary format, with empty kanji, radical, name, example
+/// and cross-reference tables
+pub fn dictionary(words: &[SampleWord]) -> Vec<u8> {
+    let mut languages: Vec<String> = Vec::new();
+    for (lang, _) in words.iter().flat_map(|word| &word.glosses) {
+        if !languages.contains(lang) {
+            languages.push(lang.clone());
+        }
+    }
+
+    let mut pool = StringPool::default();
+    let mut entries = Vec::new();
+    let mut entry_offsets = Vec::new();
+    let mut details = Vec::new();
+    let mut detail_offsets = Vec::new();
+    let mut ids = Vec::new();
+    for word in words {
+        let id = pool.get_or_insert(&word.id);
+        let kanji: Vec<u32> = word.kanji.iter().map(|text| pool.get_or_insert(text)).collect();
+        let kana: Vec<u32> = word.kana.iter().map(|text| pool.get_or_insert(text)).collect();
+        let glosses: Vec<u32> = word.glosses.iter().map(|(_, text)| pool.get_or_insert(text)).collect();
+        let pos = pool.get_or_insert("n");
+        ids.push(id);
+
+        // Same layout as codegen's main.rs: id, counts, common flag, string
+        // indices, accent groups, then gloss languages and senses
+        entry_offsets.push(entries.len() as u32);
+        entries.extend(id.to_le_bytes());
+        for count in [kanji.len(), kana.len(), glosses.len(), 1] {
+            entries.extend((count as u16).to_le_bytes());
+        }
+        entries.push(word.common as u8);
+        for index in kanji.iter().chain(&kana).chain(&glosses).chain([&pos]) {
+            entries.extend(index.to_le_bytes());
+        }
+        for _ in &kana {
+            entries.extend([1, 0]);
+        }
+        for (lang, _) in &word.glosses {
+            entries.push(languages.iter().position(|known| known == lang).unwrap_or_default() as u8);
+        }
+        entries.extend(std::iter::repeat_n(0, glosses.len()));
+
+        // Same layout as codegen's details.rs: no tags or priorities on the
+        // forms, and the one sense's part of speech
+        detail_offsets.push(details.len() as u32);
+        for _ in kanji.iter().chain(&kana) {
+            details.extend([word.common as u32, 0, 0]);
+        }
+        details.extend([1, 0, 0, 0, 0, pos]);
+    }
+    detail_offsets.push(details.len() as u32);
+
+    let mut id_index: Vec<u32> = (0..words.len() as u32).collect();
+    id_index.sort_by(|&a, &b| pool.get(ids[a as usize]).cmp(pool.get(ids[b as usize])));
+
+    let mut data = DataWriter::new(Vec::new(), Format::Binary);
+    data.u8_array("JMDICT_STRINGS", pool.data);
+    data.u8_array("JMDICT_ENTRIES", entries);
+    data.u32_array("JMDICT_ENTRY_OFFSETS", entry_offsets);
+    data.u32_array("JMDICT_STRING_OFFSETS", pool.offsets);
+    data.u32_array("JMDICT_ID_INDEX", id_index);
+    data.u32_array("ENTRY_DETAILS", details);
+    data.u32_array("ENTRY_DETAIL_OFFSETS", detail_offsets);
+    data.strings("GLOSS_LANGUAGES", languages);
+    for name in ["KANJIDIC_STRINGS", "KANJIDIC_ENTRIES", "RADICAL_STROKES", "NAMES_STRINGS", "NAMES_ENTRIES", "EXAMPLE_STRINGS"] {
+        data.u8_array(name, Vec::new());
+    }
+    for name in [
+        "KANJIDIC_STRING_OFFSETS",
+        "KANJIDIC_ENTRY_OFFSETS",
+        "KANJIDIC_LITERALS",
+        "RADICALS",
+        "RADICAL_KANJI_OFFSETS",
+        "RADICAL_KANJI",
+        "KRAD_LITERALS",
+        "KRAD_COMPONENT_OFFSETS",
+        "KRAD_COMPONENTS",
+        "NAMES_STRING_OFFSETS",
+        "NAMES_ENTRY_OFFSETS",
+        "EXAMPLE_STRING_OFFSETS",
+        "EXAMPLE_SENTENCES",
+        "EXAMPLE_ENTRY_IDS",
+        "EXAMPLE_LINK_OFFSETS",
+        "EXAMPLE_LINKS",
+        "XREF_ENTRY_IDS",
+        "XREF_LINK_OFFSETS",
+        "XREF_LINKS",
+    ] {
+        data.u32_array(name, Vec::new());
+    }
+    data.usize_count("WORD_COUNT", words.len());
+    data.usize_count("KANJIDIC_COUNT", 0);
+    data.usize_count("NAME_COUNT", 0);
+    data.text("JMDICT_VERSION", "3.6.1");
+    data.text("JMDICT_DATE", "2025-08-18");
+    data.usize_count("ENTRY_LIMIT", 0);
+    data.text("CODEGEN_FILTERS", "");
+    data.text("CODEGEN_VERSION", "0.1.0");
+    data.u64_value("DATA_CHECKSUM", data.checksum());
+    data.finish()
+}
@@ -0,0 +1,57 @@
+//! Cross-references between JMdict entries
+//!
+//! Senses point at related words and antonyms by written form and optional
+//! reading. A reference can point further down the file, so they are collected
+//! while streaming and resolved to entry ids once every word has been packed.
+
+use std::collections::BTreeMap;
+
+pub const KIND_RELATED: u32 = 0;
+pub const KIND_ANTONYM: u32 = 1;
+
+/// A cross-reference as written in the source, waiting to be resolved
+pub struct PendingXref {
+    pub source: u32,
+    pub kind: u32,
+    pub form: String,
+    pub reading: Option<String>,
+}
+
+/// Packed cross-references as `(target id, kind)` pairs, grouped per entry
+/// id: entry `entry_ids[i]` owns `links[link_offsets[i]..link_offsets[i + 1]]`
+/// (in pairs).
+#[derive(Default)]
+pub struct XrefTable {
+    pub entry_ids: Vec<u32>,
+    pub link_offsets: Vec<u32>,
+    pub links: Vec<u32>,
+}
+
+/// Resolve references to entry ids with `resolve`, dropping the ones to words
+/// that weren't packed and references from a word to itself.
+pub fn pack(pending: &[PendingXref], resolve: impl Fn(&str, Option<&str>) -> Option<u32>) -> XrefTable {
+    let mut grouped: BTreeMap<u32, Vec<(u32, u32)>> = BTreeMap::new();
+    for xref in pending {
+        let Some(target) = resolve(&xref.form, xref.reading.as_deref()) else {
+            continue;
+        };
+        if target == xref.source {
+            continue;
+        }
+        let links = grouped.entry(xref.source).or_default();
+        if !links.contains(&(target, xref.kind)) {
+            links.push((target, xref.kind));
+        }
+    }
+
+    let mut table = XrefTable::default();
+    table.link_offsets.push(0);
+    for (entry_id, links) in grouped {
+        for (target, kind) in links {
+            table.links.extend([target, kind]);
+        }
+        table.entry_ids.push(entry_id);
+        table.link_offsets.push((table.links.len() / 2) as u32);
+    }
+    table
+}